@@ -0,0 +1,29 @@
+//! Serves the engine's text protocol (see `ttt_engine::protocol`) on stdin and stdout,
+//! for tournament harnesses, bots and third-party front-ends.
+//!
+//! `printf 'position moves M4 H0\ngo\n' | ttt-protocol`
+
+use std::io::{self, BufRead, Write};
+use ttt_engine::protocol::Session;
+
+fn main() {
+    env_logger::init();
+    let mut session = Session::new();
+    let mut stdout = io::stdout().lock();
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if let Some(response) = session.execute(&line) {
+            if write!(stdout, "{}", response)
+                .and_then(|_| stdout.flush())
+                .is_err()
+            {
+                break;
+            }
+        }
+        if session.has_quit() {
+            break;
+        }
+    }
+}
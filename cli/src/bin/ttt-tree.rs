@@ -0,0 +1,79 @@
+//! Writes the complete game tree of classic tic-tac-toe (see `ttt_engine::game_tree`)
+//! as a JSON or CSV report, and checks the engine's minimax against it.
+//!
+//! `ttt-tree --format csv --output tree.csv`, `ttt-tree --check`
+
+use std::fs;
+use std::io::{self, Write};
+use std::process::exit;
+use ttt_engine::game_tree::GameTree;
+
+const USAGE: &str = "usage: ttt-tree [--format json|csv] [--output <file>] [--check]";
+
+fn main() {
+    env_logger::init();
+    let mut format = "json".to_string();
+    let mut output: Option<String> = None;
+    let mut check = false;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = args.next().unwrap_or_default(),
+            "--output" => output = args.next(),
+            "--check" => check = true,
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => {
+                eprintln!("unknown argument '{}'\n{}", arg, USAGE);
+                exit(2);
+            }
+        }
+    }
+
+    let tree = GameTree::build();
+    if check {
+        let disagreements = tree.check_minimax();
+        for disagreement in &disagreements {
+            eprintln!("{}", disagreement);
+        }
+        if !disagreements.is_empty() {
+            eprintln!(
+                "minimax disagrees with the game tree on {} positions",
+                disagreements.len()
+            );
+            exit(1);
+        }
+        eprintln!(
+            "minimax agrees with the game tree on all {} positions",
+            tree.positions.len()
+        );
+        if output.is_none() {
+            return;
+        }
+    }
+
+    let report = match format.as_str() {
+        "json" => tree.to_json(),
+        "csv" => tree.to_csv(),
+        _ => {
+            eprintln!("unknown format '{}'\n{}", format, USAGE);
+            exit(2);
+        }
+    };
+    match output {
+        Some(path) => {
+            if let Err(error) = fs::write(&path, report) {
+                eprintln!("Can't write {}: {}", path, error);
+                exit(1);
+            }
+        }
+        None => {
+            // A closed pipe (`ttt-tree | head`) just ends the output
+            if io::stdout().write_all(report.as_bytes()).is_err() {
+                exit(1);
+            }
+        }
+    }
+}
@@ -0,0 +1,415 @@
+//! Terminal front-end: the same variants, modes and strategies as the window,
+//! with the board drawn in ASCII. Useful over SSH and without a display.
+//!
+//! Moves are entered as a cell number (1-9 on the 3×3 board, counted row by row from
+//! the top left) or as coordinates like `b2` (column letter, row number), followed by
+//! the mark in variants where the player picks it (`b2 x`).
+
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::process::exit;
+use ttt_engine::game::{Game, Marks, Variant};
+use ttt_engine::mode::GameMode;
+use ttt_engine::notation::{Coordinates, Notation};
+use ttt_engine::record::{self, GameRecord};
+use ttt_engine::rules::{Mark, Move, Outcome, Player};
+use ttt_engine::strategy::{all_strategies, strategy_by_name, Strategy};
+use ttt_engine::win_graph::WinGraph;
+
+const USAGE: &str = "usage: ttt-cli [--variant <name|number>] [--mode 1p|2p|self]
+               [--difficulty <strategy>] [--opponent <strategy>]
+               [--first machine|me] [--symbol o|x]
+
+  --variant     board to play on (default 3×3), see the list below
+  --mode        1p: against the engine, 2p: two people, self: engine against engine
+  --difficulty  strategy of the engine (the second side in self-play), default Perfect
+  --opponent    strategy of the first side in self-play, default Random
+  --first       who opens against the engine, default machine
+  --symbol      your symbol where each side has its own, default o";
+
+const COMMANDS: &str =
+    "Enter a cell number or coordinates (e.g. 5 or b2), adding the mark where you pick it (b2 x).
+Other commands: undo, save <file>, load <file>, help, quit,
+dot [all] <file> to write the Machine's win graph from here (or all of it) for Graphviz";
+
+/// Settings from the command line, with the defaults of the window's selectors.
+struct Options {
+    variant: Variant,
+    mode: GameMode,
+    difficulty: Box<dyn Strategy>,
+    opponent: Box<dyn Strategy>,
+    first_mover: Player,
+    human_symbol: Mark,
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}\n\n{}\n\nvariants:", message, USAGE);
+    for (index, variant) in Variant::all().into_iter().enumerate() {
+        eprintln!("  {:>2}: {}", index, variant.name());
+    }
+    let names: Vec<&str> = all_strategies().iter().map(|s| s.name()).collect();
+    eprintln!("strategies: {}", names.join(", "));
+    exit(2);
+}
+
+fn parse_options() -> Options {
+    let mut options = Options {
+        variant: Variant::default(),
+        mode: GameMode::VsMachine,
+        difficulty: strategy_by_name("Perfect").unwrap(),
+        opponent: strategy_by_name("Random").unwrap(),
+        first_mover: Player::Machine,
+        human_symbol: Mark::O,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            usage_error("Play tic-tac-toe in the terminal.");
+        }
+        let Some(value) = args.next() else {
+            usage_error(&format!("Missing value for {}", flag));
+        };
+        let invalid = || -> ! { usage_error(&format!("Invalid value for {}: {}", flag, value)) };
+        match flag.as_str() {
+            "--variant" => {
+                options.variant = Variant::from_name(&value).unwrap_or_else(|| invalid())
+            }
+            "--mode" => {
+                options.mode = match value.as_str() {
+                    "1p" => GameMode::VsMachine,
+                    "2p" => GameMode::Hotseat,
+                    "self" => GameMode::SelfPlay,
+                    _ => invalid(),
+                }
+            }
+            "--difficulty" => {
+                options.difficulty = strategy_by_name(&value).unwrap_or_else(|| invalid())
+            }
+            "--opponent" => {
+                options.opponent = strategy_by_name(&value).unwrap_or_else(|| invalid())
+            }
+            "--first" => {
+                options.first_mover = match value.as_str() {
+                    "machine" => Player::Machine,
+                    "me" => Player::Human,
+                    _ => invalid(),
+                }
+            }
+            "--symbol" => {
+                options.human_symbol =
+                    Mark::from_letter(&value.to_uppercase()).unwrap_or_else(|| invalid())
+            }
+            _ => usage_error(&format!("Unknown option {}", flag)),
+        }
+    }
+
+    // Two people sharing the board always start with Player 1 (the Human side)
+    if options.mode == GameMode::Hotseat {
+        options.first_mover = Player::Human;
+    }
+    options
+}
+
+/// The symbol drawn on cell `idx`: its mark, `.` if it can be played now,
+/// or `-` if it is out of play (e.g. outside the active sub-board).
+fn symbol(game: &dyn Game, idx: usize, human_symbol: Mark, playable: &[usize]) -> char {
+    let mark = game.mark(idx).or_else(|| {
+        game.cell(idx).map(|player| match player {
+            Player::Human => human_symbol,
+            Player::Machine => human_symbol.other(),
+        })
+    });
+    match mark {
+        Some(mark) => mark.letter().chars().next().unwrap_or('?'),
+        None if playable.contains(&idx) => '.',
+        None => '-',
+    }
+}
+
+fn draw(game: &dyn Game, coordinates: &Coordinates, human_symbol: Mark) -> String {
+    let playable: Vec<usize> = game.legal_moves().iter().map(|mv| mv.idx).collect();
+    let mut text = String::from("   ");
+    for column in 0..coordinates.columns() {
+        if coordinates.gap_before_column(column) {
+            text.push_str("  ");
+        }
+        text.push(' ');
+        text.push(Coordinates::column_letter(column));
+    }
+    text.push('\n');
+
+    for row in 0..coordinates.rows() {
+        if coordinates.gap_before_row(row) {
+            text.push('\n');
+        }
+        text.push_str(&format!("{:>2} ", row + 1));
+        for column in 0..coordinates.columns() {
+            if coordinates.gap_before_column(column) {
+                text.push_str("  ");
+            }
+            text.push(' ');
+            text.push(match coordinates.cell_at(column, row) {
+                Some(idx) => symbol(game, idx, human_symbol, &playable),
+                None => ' ',
+            });
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Reads a move for the side to move: a cell number or coordinates, and the mark
+/// in variants where the player picks it.
+fn parse_move(input: &str, game: &dyn Game, coordinates: &Coordinates) -> Result<Move, String> {
+    let mut text: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+
+    // Cells always end in a digit, so a trailing x or o is the mark
+    let mark = match text.chars().last() {
+        Some(c) if text.len() > 1 && (c == 'x' || c == 'o') => {
+            text.pop();
+            Mark::from_letter(&c.to_uppercase().to_string())
+        }
+        _ => None,
+    };
+
+    let idx = if text.chars().all(|c| c.is_ascii_digit()) && !text.is_empty() {
+        let number: usize = text.parse().map_err(|_| format!("Invalid cell {}", text))?;
+        if number == 0 || number > game.cell_count() {
+            return Err(format!("Cell numbers go from 1 to {}", game.cell_count()));
+        }
+        number - 1
+    } else {
+        coordinates.cell(&text).ok_or_else(|| {
+            format!(
+                "Can't read '{}' as a move; type help for the notation",
+                input.trim()
+            )
+        })?
+    };
+
+    let mv = Move::new(idx, game.to_move());
+    let mv = match (game.marks(), mark) {
+        (Marks::Chosen, Some(mark)) => mv.with_mark(mark),
+        (Marks::Chosen, None) => {
+            return Err(format!(
+                "Say which mark to place, e.g. {} x",
+                coordinates.name(idx)
+            ))
+        }
+        (_, Some(_)) => return Err("Marks can't be chosen in this variant".to_string()),
+        (_, None) => mv,
+    };
+
+    if game.legal_moves().contains(&mv) {
+        Ok(mv)
+    } else if game.cell(idx).is_some() {
+        Err(format!("{} is already taken", coordinates.name(idx)))
+    } else {
+        Err(format!("{} can't be played now", coordinates.name(idx)))
+    }
+}
+
+/// `mv` as typed at the prompt, for the move log.
+fn describe(mv: &Move, coordinates: &Coordinates) -> String {
+    match mv.mark {
+        Some(mark) => format!("{} {}", coordinates.name(mv.idx), mark.letter()),
+        None => coordinates.name(mv.idx),
+    }
+}
+
+/// The position after `moves`; an empty board opens with `first_mover`.
+fn position(variant: Variant, first_mover: Player, moves: &[Move]) -> Box<dyn Game> {
+    if moves.is_empty() {
+        variant.new_game(first_mover)
+    } else {
+        variant.replay(moves)
+    }
+}
+
+/// Who played `player`'s side, for the record.
+fn player_name(options: &Options, player: Player) -> String {
+    options
+        .mode
+        .player_name(player, options.difficulty.name(), options.opponent.name())
+}
+
+fn main() {
+    env_logger::init();
+    let mut options = parse_options();
+    let mode = options.mode;
+    let mut variant = options.variant;
+    let mut moves: Vec<Move> = Vec::new();
+    let mut game = variant.new_game(options.first_mover);
+    let mut coordinates = Coordinates::new(game.as_ref());
+    let mut lines = io::stdin().lock().lines();
+
+    println!("{}\n{}\n", variant.name(), COMMANDS);
+    let mut show_board = true;
+    loop {
+        let outcome = game.outcome();
+        let turn = game.to_move();
+        let status = mode.status_text(&outcome, turn);
+        let game_over = outcome != Outcome::InProgress;
+        if show_board {
+            println!(
+                "{}",
+                draw(game.as_ref(), &coordinates, options.human_symbol)
+            );
+            if let Outcome::Win(_, line) = &outcome {
+                if !line.is_empty() {
+                    let cells: Vec<String> =
+                        line.iter().map(|&idx| coordinates.name(idx)).collect();
+                    println!("Winning line: {}", cells.join(" "));
+                }
+            }
+        }
+        show_board = true;
+
+        if !game_over && !mode.is_person(turn) {
+            // In self-play the Human side is played by the opponent strategy
+            let strategy = match turn {
+                Player::Human => options.opponent.as_mut(),
+                Player::Machine => options.difficulty.as_mut(),
+            };
+            let Some(mv) = strategy.choose_move(game.as_ref()) else {
+                println!("{} found no move", strategy.name());
+                break;
+            };
+            println!(
+                "{} ({}) plays {}",
+                mode.side_name(turn),
+                strategy.name(),
+                describe(&mv, &coordinates)
+            );
+            game.play(mv);
+            moves.push(mv);
+            continue;
+        }
+
+        // Once the game is over, it can still be saved or taken back
+        let prompt = match (game_over, status.is_empty()) {
+            (true, _) => format!("{} (save, load, undo or quit)", status),
+            (false, true) => "Your move".to_string(),
+            (false, false) => status,
+        };
+        print!("{}> ", prompt);
+        io::stdout().flush().ok();
+        let Some(Ok(line)) = lines.next() else {
+            println!();
+            break;
+        };
+
+        let (command, argument) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+        let argument = argument.trim();
+        match command.to_lowercase().as_str() {
+            "" => show_board = false,
+            "q" | "quit" | "exit" => break,
+            "h" | "help" | "?" => {
+                println!("{}\n", COMMANDS);
+                show_board = false;
+            }
+            "u" | "undo" => {
+                // Take back the last move of a person and the engine replies after it
+                if !moves.iter().any(|mv| mode.is_person(mv.player)) {
+                    println!("Nothing to undo\n");
+                    show_board = false;
+                    continue;
+                }
+                while let Some(mv) = moves.pop() {
+                    if mode.is_person(mv.player) {
+                        break;
+                    }
+                }
+                game = position(variant, options.first_mover, &moves);
+            }
+            "dot" if !argument.is_empty() => {
+                // The whole graph doesn't depend on the game; the subtree needs a 3×3 position
+                let (whole, path) = match argument.split_once(' ') {
+                    Some(("all", path)) => (true, path.trim()),
+                    _ => (false, argument),
+                };
+                let dot = if path == "all" {
+                    Err("give a file to write to, as in dot all <file>".to_string())
+                } else if whole {
+                    WinGraph::get()
+                        .to_dot(None)
+                        .ok_or_else(|| "the win graph is empty".to_string())
+                } else if let Some(board) = game.classic_board() {
+                    WinGraph::get()
+                        .to_dot(Some((&board, game.to_move())))
+                        .ok_or_else(|| {
+                            "this position isn't on any of the Machine's win paths".to_string()
+                        })
+                } else {
+                    Err("the win graph only covers the 3×3 board".to_string())
+                };
+                match dot.and_then(|dot| fs::write(path, dot).map_err(|error| error.to_string())) {
+                    Ok(()) => println!("Wrote the win graph to {}\n", path),
+                    Err(error) => println!("Can't write the win graph: {}\n", error),
+                }
+                show_board = false;
+            }
+            "save" if !argument.is_empty() => {
+                let record = GameRecord {
+                    human: player_name(&options, Player::Human),
+                    machine: player_name(&options, Player::Machine),
+                    date: record::today(),
+                    ..GameRecord::new(variant, moves.clone())
+                };
+                match fs::write(argument, record.write(Notation::Algebraic)) {
+                    Ok(()) => println!("Saved to {}\n", argument),
+                    Err(error) => println!("Can't save to {}: {}\n", argument, error),
+                }
+                show_board = false;
+            }
+            "load" if !argument.is_empty() => {
+                let loaded = fs::read_to_string(argument)
+                    .map_err(|error| error.to_string())
+                    .and_then(|text| GameRecord::parse(&text).map_err(|error| error.to_string()));
+                match loaded {
+                    Ok(record) => {
+                        println!(
+                            "Loaded a game of {} from {}\n",
+                            record.variant.name(),
+                            argument
+                        );
+                        variant = record.variant;
+                        if let Some(first) = record.moves.first() {
+                            options.first_mover = first.player;
+                        }
+                        moves = record.moves;
+                        game = position(variant, options.first_mover, &moves);
+                        coordinates = Coordinates::new(game.as_ref());
+                    }
+                    Err(error) => {
+                        println!("Can't load {}: {}\n", argument, error);
+                        show_board = false;
+                    }
+                }
+            }
+            "save" | "load" => {
+                println!("Say which file, e.g. {} game.ttt\n", command);
+                show_board = false;
+            }
+            _ if game_over => {
+                println!("The game is over\n");
+                show_board = false;
+            }
+            _ => match parse_move(&line, game.as_ref(), &coordinates) {
+                Ok(mv) => {
+                    game.play(mv);
+                    moves.push(mv);
+                }
+                Err(message) => {
+                    println!("{}\n", message);
+                    show_board = false;
+                }
+            },
+        }
+    }
+}
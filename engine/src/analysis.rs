@@ -0,0 +1,139 @@
+//! Judging a position: the strongest moves for the side to move, and how the game
+//! ends from there with best play, as far as the engine can tell.
+
+use crate::game::{Game, Variant};
+use crate::rules::{Move, Outcome};
+use crate::search;
+use crate::strategy::with_minimax;
+
+/// Plies looked ahead on boards the exact solver can't handle.
+const ANALYSIS_DEPTH: usize = 3;
+
+/// How the game ends with best play, for the side to move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Win,
+    Draw,
+    Loss,
+    /// Neither side can force a result within the search horizon.
+    Unclear,
+}
+
+impl Verdict {
+    /// The verdict for the other side of the same position.
+    pub fn reversed(self) -> Self {
+        match self {
+            Verdict::Win => Verdict::Loss,
+            Verdict::Loss => Verdict::Win,
+            verdict => verdict,
+        }
+    }
+
+    /// Orders the known verdicts from worst to best; `Unclear` has no place.
+    fn rank(self) -> Option<u8> {
+        match self {
+            Verdict::Loss => Some(0),
+            Verdict::Draw => Some(1),
+            Verdict::Win => Some(2),
+            Verdict::Unclear => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Every move that scores best; empty once the game is over.
+    pub best_moves: Vec<Move>,
+    pub verdict: Verdict,
+}
+
+/// Analyzes the position for the side to move. Classic positions are solved exactly;
+/// other variants are searched `ANALYSIS_DEPTH` plies ahead (less on wide boards, to
+/// stay within the search budget), or to the end once few cells are left.
+pub fn analyze(game: &dyn Game) -> Analysis {
+    let turn = game.to_move();
+    let (best_moves, value, exact) = match game.classic_board() {
+        Some(board) => with_minimax(|minimax| {
+            (
+                minimax
+                    .best_moves(&board, turn)
+                    .into_iter()
+                    .map(|idx| Move::new(idx, turn))
+                    .collect(),
+                minimax.evaluate(&board, turn),
+                true,
+            )
+        }),
+        None => {
+            let exact_depth = search::exact_depth(game);
+            let depth =
+                exact_depth.unwrap_or_else(|| search::depth_within_budget(game, ANALYSIS_DEPTH));
+            let (best_moves, value) = search::best_moves_and_score(game, depth);
+            // Only forced results count; heuristic scores say nothing about the end
+            let value = value.filter(|value| value.abs() >= search::WIN_SCORE);
+            (best_moves, value.unwrap_or(0), exact_depth.is_some())
+        }
+    };
+
+    let verdict = match value.signum() {
+        1 => Verdict::Win,
+        0 if exact => Verdict::Draw,
+        0 => Verdict::Unclear,
+        _ => Verdict::Loss,
+    };
+    Analysis {
+        best_moves,
+        verdict,
+    }
+}
+
+/// How one move of a game changed its course, from the mover's side.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MoveReview {
+    pub mv: Move,
+    /// How the game ends with best play before the move ...
+    pub before: Verdict,
+    /// ... and after it.
+    pub after: Verdict,
+    /// True if the move is one of the strongest in its position.
+    pub best: bool,
+}
+
+impl MoveReview {
+    /// True if the move made the result under best play worse for the mover,
+    /// e.g. a won game becomes a draw. Moves judged `Unclear` on either side are never
+    /// blunders, the engine can't tell.
+    pub fn is_blunder(&self) -> bool {
+        match (self.before.rank(), self.after.rank()) {
+            (Some(before), Some(after)) => after < before,
+            _ => false,
+        }
+    }
+}
+
+/// Reviews move `index` of a game of `variant`, with the moves before it taken from
+/// `moves`. `None` if there is no such move. Costs two analyses, one before the move
+/// and one after it, so a viewer can judge moves one at a time as they are shown.
+pub fn review_move(variant: Variant, moves: &[Move], index: usize) -> Option<MoveReview> {
+    let &mv = moves.get(index)?;
+    // The first move decides who opened, also when reviewing that move
+    let mut game = variant.new_game(moves[0].player);
+    for &earlier in &moves[..index] {
+        game.play(earlier);
+    }
+    let before = analyze(game.as_ref());
+    game.play(mv);
+    // Once the game is over, the result itself is the verdict
+    let after = match game.outcome() {
+        Outcome::InProgress => analyze(game.as_ref()).verdict.reversed(),
+        Outcome::Win(player, _) if player == mv.player => Verdict::Win,
+        Outcome::Win(_, _) => Verdict::Loss,
+        Outcome::Draw => Verdict::Draw,
+    };
+    Some(MoveReview {
+        mv,
+        before: before.verdict,
+        after,
+        best: before.best_moves.contains(&mv),
+    })
+}
@@ -0,0 +1,264 @@
+//! Game variants: the `Game` trait every playable board implements, and the
+//! `Variant` configurations the UI and self-play can start a game from.
+
+use crate::mnk::MnkGame;
+use crate::notakto::NotaktoGame;
+use crate::rules::{Board, Mark, Move, Outcome, Player};
+use crate::ultimate::UltimateGame;
+use crate::wild::WildGame;
+
+/// A position of some tic-tac-toe variant, with the side to move.
+///
+/// Cells are numbered `0..cell_count()`; those numbers are the `idx` of every `Move`
+/// (and the tile ids of the window).
+pub trait Game {
+    fn cell_count(&self) -> usize;
+
+    /// Where the tile of cell `idx` is drawn, in tile units from the top-left corner.
+    fn cell_position(&self, idx: usize) -> (f32, f32);
+
+    /// Width and height of the drawing, in tile units.
+    fn grid_size(&self) -> (f32, f32);
+
+    /// Who placed the mark on cell `idx`, if anyone.
+    fn cell(&self, idx: usize) -> Option<Player>;
+
+    /// How the marks are decided in this variant.
+    fn marks(&self) -> Marks {
+        Marks::PerSide
+    }
+
+    /// The mark on cell `idx`, in variants where it isn't the placing side's own symbol.
+    fn mark(&self, idx: usize) -> Option<Mark> {
+        match self.marks() {
+            Marks::AllX => self.cell(idx).map(|_| Mark::X),
+            Marks::PerSide | Marks::Chosen => None,
+        }
+    }
+
+    fn to_move(&self) -> Player;
+
+    /// The moves the side to move may make; none once the game is over.
+    /// They carry a mark only when `marks()` is `Marks::Chosen`.
+    fn legal_moves(&self) -> Vec<Move>;
+
+    /// The moves worth searching, most promising first. Defaults to every legal move;
+    /// large boards restrict it to the neighbourhood of the stones already played.
+    fn candidate_moves(&self) -> Vec<Move> {
+        self.legal_moves()
+    }
+
+    /// The moves the Machine picks from when it opens the game.
+    fn opening_moves(&self) -> Vec<Move> {
+        self.legal_moves()
+    }
+
+    /// Plays `mv` for the side to move and passes the turn; illegal moves are ignored.
+    fn play(&mut self, mv: Move);
+
+    fn outcome(&self) -> Outcome;
+
+    /// Heuristic value of the position for `player`: positive if `player` stands better.
+    /// Must stay well below `search::WIN_SCORE`.
+    fn evaluate(&self, player: Player) -> i32;
+
+    /// The position as a classic 3×3 `Board`, if this is classic tic-tac-toe;
+    /// those are solved exactly by `Minimax` instead of the heuristic search.
+    fn classic_board(&self) -> Option<Board> {
+        None
+    }
+
+    /// Cells to highlight as the only ones in play, e.g. the sub-board the side to move
+    /// is sent to in ultimate tic-tac-toe. Empty when nothing needs pointing out.
+    fn active_cells(&self) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// Lines that decided part of the board without ending the game (e.g. claimed
+    /// sub-boards), with the side that completed them.
+    fn decided_lines(&self) -> Vec<(Player, Vec<usize>)> {
+        Vec::new()
+    }
+
+    fn clone_box(&self) -> Box<dyn Game>;
+}
+
+/// Why `mv` can't be played in `game`, for error messages; `None` if it is legal.
+pub fn illegal_reason(game: &dyn Game, mv: &Move) -> Option<String> {
+    if game.legal_moves().contains(mv) {
+        return None;
+    }
+    Some(if game.outcome() != Outcome::InProgress {
+        "the game is already over".to_string()
+    } else if mv.idx >= game.cell_count() {
+        format!("there is no cell {}", mv.idx)
+    } else if mv.player != game.to_move() {
+        format!("it is {}'s turn", game.to_move().letter())
+    } else if game.cell(mv.idx).is_some() {
+        "the cell is taken".to_string()
+    } else if game.marks() == Marks::Chosen && mv.mark.is_none() {
+        "the move must say which mark it places".to_string()
+    } else if game.marks() != Marks::Chosen && mv.mark.is_some() {
+        "marks can't be chosen in this variant".to_string()
+    } else {
+        "the cell is not in play".to_string()
+    })
+}
+
+/// How the marks on the board are decided.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Marks {
+    /// Each side always places its own symbol, chosen in the UI.
+    PerSide,
+    /// Both sides place X (Notakto).
+    AllX,
+    /// Every move picks X or O (wild tic-tac-toe, Order and Chaos).
+    Chosen,
+}
+
+/// The rules a game is played by; the index matches the variant selector of the window.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Variant {
+    /// `width`×`height` board, `k` in a row wins.
+    Mnk {
+        width: usize,
+        height: usize,
+        k: usize,
+    },
+    /// Nine classic boards; each move sends the opponent to a sub-board.
+    Ultimate,
+    /// Classic board where completing a line loses.
+    Misere,
+    /// 3D tic-tac-toe on a `size`×`size`×`size` cube; 4 is Qubic.
+    Cube { size: usize },
+    /// Both sides place X on `boards` boards; killing the last live board loses.
+    Notakto { boards: usize },
+    /// Classic board where each move places X or O; three alike in a row win for the mover.
+    Wild,
+    /// 6×6 board where each move places X or O; five alike in a row win for the opener
+    /// (Order), a full board without them for the other side (Chaos).
+    OrderAndChaos,
+}
+
+impl Default for Variant {
+    fn default() -> Self {
+        Variant::Mnk {
+            width: 3,
+            height: 3,
+            k: 3,
+        }
+    }
+}
+
+impl Variant {
+    /// Every variant, in the order of the variant selector.
+    pub fn all() -> Vec<Variant> {
+        vec![
+            Variant::default(),
+            Variant::Mnk {
+                width: 4,
+                height: 4,
+                k: 4,
+            },
+            Variant::Mnk {
+                width: 5,
+                height: 5,
+                k: 4,
+            },
+            Variant::Mnk {
+                width: 15,
+                height: 15,
+                k: 5,
+            },
+            Variant::Ultimate,
+            Variant::Misere,
+            Variant::Notakto { boards: 1 },
+            Variant::Notakto { boards: 3 },
+            Variant::Cube { size: 3 },
+            Variant::Cube { size: 4 },
+            Variant::Wild,
+            Variant::OrderAndChaos,
+        ]
+    }
+
+    /// Maps the selector index from the UI; unknown indices fall back to the default.
+    pub fn from_index(index: i32) -> Self {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| Self::all().into_iter().nth(index))
+            .unwrap_or_default()
+    }
+
+    /// The selector index of the variant, the inverse of `from_index`.
+    pub fn index(self) -> i32 {
+        Self::all()
+            .iter()
+            .position(|&variant| variant == self)
+            .map_or(0, |index| index as i32)
+    }
+
+    /// Looks a variant up by its index in `all` or by its name; names match loosely,
+    /// so they can be typed without `×` and accents (`3x3`, `misere`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let all = Self::all();
+        if let Ok(index) = name.parse::<usize>() {
+            return all.get(index).copied();
+        }
+        let normalize = |name: &str| name.to_lowercase().replace('×', "x").replace('è', "e");
+        all.into_iter()
+            .find(|variant| normalize(&variant.name()) == normalize(name))
+    }
+
+    /// Name shown in the variant selector.
+    pub fn name(self) -> String {
+        match self {
+            Variant::Mnk {
+                width: 3,
+                height: 3,
+                k: 3,
+            } => "3×3".to_string(),
+            Variant::Mnk {
+                width: 15,
+                height: 15,
+                k: 5,
+            } => "Gomoku".to_string(),
+            Variant::Mnk { width, height, k } => format!("{}×{}, {} in a row", width, height, k),
+            Variant::Ultimate => "Ultimate".to_string(),
+            Variant::Misere => "Misère".to_string(),
+            Variant::Notakto { boards: 1 } => "Notakto".to_string(),
+            Variant::Notakto { boards } => format!("Notakto on {} boards", boards),
+            Variant::Cube { size: 4 } => "Qubic".to_string(),
+            Variant::Cube { size } => format!("{0}×{0}×{0}", size),
+            Variant::Wild => "Wild".to_string(),
+            Variant::OrderAndChaos => "Order and Chaos".to_string(),
+        }
+    }
+
+    /// An empty board with `first_player` to move.
+    pub fn new_game(self, first_player: Player) -> Box<dyn Game> {
+        match self {
+            Variant::Mnk { width, height, k } => {
+                Box::new(MnkGame::new(width, height, k, first_player))
+            }
+            Variant::Ultimate => Box::new(UltimateGame::new(first_player)),
+            Variant::Misere => Box::new(MnkGame::new(3, 3, 3, first_player).misere()),
+            Variant::Notakto { boards } => Box::new(NotaktoGame::new(boards, first_player)),
+            Variant::Cube { size } => Box::new(MnkGame::cube(size, first_player)),
+            Variant::Wild => Box::new(WildGame::wild(first_player)),
+            Variant::OrderAndChaos => Box::new(WildGame::order_and_chaos(first_player)),
+        }
+    }
+
+    /// Replays `moves` from an empty board; the first move decides who opened.
+    /// Illegal moves (wrong side, taken cell, missing or unexpected mark) are skipped.
+    pub fn replay(self, moves: &[Move]) -> Box<dyn Game> {
+        let first_player = moves.first().map_or(Player::Human, |mv| mv.player);
+        let mut game = self.new_game(first_player);
+        for mv in moves {
+            if game.legal_moves().contains(mv) {
+                game.play(*mv);
+            }
+        }
+        game
+    }
+}
@@ -0,0 +1,360 @@
+//! The complete game tree of classic tic-tac-toe, where `win_graph` keeps only the short
+//! Machine wins: every position that can come up, how many games pass through it, and
+//! who wins it with best play. Small enough to build in full (5478 positions), it serves
+//! as a reference the engine can be checked against.
+//!
+//! Positions are seen from the opener's side: the first mover plays `x`, the second `o`.
+
+use crate::minimax::Minimax;
+use crate::rules::{Board, Player};
+use std::collections::HashMap;
+
+/// Who plays `x` in the tree; which side that is doesn't change anything.
+const FIRST_MOVER: Player = Player::Machine;
+
+/// Result of a game, or of a position with best play.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Value {
+    FirstMoverWins,
+    Draw,
+    SecondMoverWins,
+}
+
+impl Value {
+    pub fn name(self) -> &'static str {
+        match self {
+            Value::FirstMoverWins => "first_mover_wins",
+            Value::Draw => "draw",
+            Value::SecondMoverWins => "second_mover_wins",
+        }
+    }
+
+    fn of_winner(winner: Option<Player>) -> Self {
+        match winner {
+            Some(FIRST_MOVER) => Value::FirstMoverWins,
+            Some(_) => Value::SecondMoverWins,
+            None => Value::Draw,
+        }
+    }
+
+    /// How good the value is for `player`, the higher the better.
+    fn score_for(self, player: Player) -> i32 {
+        let score = match self {
+            Value::FirstMoverWins => 1,
+            Value::Draw => 0,
+            Value::SecondMoverWins => -1,
+        };
+        if player == FIRST_MOVER {
+            score
+        } else {
+            -score
+        }
+    }
+}
+
+/// Numbers of complete games by result.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct GameCounts {
+    pub first_mover_wins: u64,
+    pub second_mover_wins: u64,
+    pub draws: u64,
+}
+
+impl GameCounts {
+    pub fn total(&self) -> u64 {
+        self.first_mover_wins + self.second_mover_wins + self.draws
+    }
+
+    fn add(&mut self, other: GameCounts, times: u64) {
+        self.first_mover_wins += other.first_mover_wins * times;
+        self.second_mover_wins += other.second_mover_wins * times;
+        self.draws += other.draws * times;
+    }
+}
+
+/// One reachable position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+    pub board: Board,
+    /// Moves played so far.
+    pub depth: usize,
+    /// True if the game is over: a line is complete or the board is full.
+    pub terminal: bool,
+    /// The result with best play from here.
+    pub value: Value,
+    /// The complete games that pass through this position, by result.
+    pub games: GameCounts,
+}
+
+impl Position {
+    /// The side to move, `None` once the game is over.
+    pub fn to_move(&self) -> Option<Player> {
+        match (self.terminal, self.depth % 2) {
+            (true, _) => None,
+            (false, 0) => Some(FIRST_MOVER),
+            (false, _) => Some(FIRST_MOVER.opponent()),
+        }
+    }
+
+    /// The cells row by row: `x` and `o` for the first and second mover, `.` for empty.
+    pub fn board_text(&self) -> String {
+        (0..9)
+            .map(|idx| match self.board.get(idx) {
+                Some(FIRST_MOVER) => 'x',
+                Some(_) => 'o',
+                None => '.',
+            })
+            .collect()
+    }
+}
+
+/// Every reachable position, ordered by depth and then by board.
+pub struct GameTree {
+    pub positions: Vec<Position>,
+}
+
+/// The games from `board` on, with `turn` to move, and the value with best play.
+/// Memoized per board: the side to move follows from the number of marks.
+fn solve(
+    board: Board,
+    turn: Player,
+    memo: &mut HashMap<Board, (GameCounts, Value)>,
+) -> (GameCounts, Value) {
+    if let Some(&known) = memo.get(&board) {
+        return known;
+    }
+    let solved = if board.winner().is_some() || board.is_full() {
+        let value = Value::of_winner(board.winner());
+        let mut games = GameCounts::default();
+        match value {
+            Value::FirstMoverWins => games.first_mover_wins = 1,
+            Value::SecondMoverWins => games.second_mover_wins = 1,
+            Value::Draw => games.draws = 1,
+        }
+        (games, value)
+    } else {
+        let mut games = GameCounts::default();
+        let mut best: Option<Value> = None;
+        for idx in board.empty_cells() {
+            let (child_games, child_value) = solve(board.with(idx, turn), turn.opponent(), memo);
+            games.add(child_games, 1);
+            if best.is_none_or(|best| child_value.score_for(turn) > best.score_for(turn)) {
+                best = Some(child_value);
+            }
+        }
+        (games, best.unwrap_or(Value::Draw))
+    };
+    memo.insert(board, solved);
+    solved
+}
+
+impl GameTree {
+    /// Enumerates the whole tree. Positions are counted once however many move orders
+    /// reach them; symmetric positions are counted separately.
+    pub fn build() -> Self {
+        let mut memo = HashMap::new();
+        let mut positions = Vec::new();
+        // Number of move orders reaching each position of the current depth
+        let mut layer: HashMap<Board, u64> = HashMap::from([(Board::new(), 1)]);
+        for depth in 0..=9 {
+            let turn = if depth % 2 == 0 {
+                FIRST_MOVER
+            } else {
+                FIRST_MOVER.opponent()
+            };
+            let mut next: HashMap<Board, u64> = HashMap::new();
+            for (&board, &paths) in &layer {
+                let (completions, value) = solve(board, turn, &mut memo);
+                let mut games = GameCounts::default();
+                games.add(completions, paths);
+                let terminal = board.winner().is_some() || board.is_full();
+                positions.push(Position {
+                    board,
+                    depth,
+                    terminal,
+                    value,
+                    games,
+                });
+                if !terminal {
+                    for idx in board.empty_cells() {
+                        *next.entry(board.with(idx, turn)).or_default() += paths;
+                    }
+                }
+            }
+            layer = next;
+        }
+        positions.sort_by_key(|position| (position.depth, position.board_text()));
+        GameTree { positions }
+    }
+
+    /// All complete games, by result: the counts of the empty board.
+    pub fn games(&self) -> GameCounts {
+        self.positions
+            .first()
+            .map_or_else(GameCounts::default, |root| root.games)
+    }
+
+    /// Number of positions after 0, 1, ... 9 moves.
+    pub fn positions_per_depth(&self) -> Vec<usize> {
+        let mut counts = vec![0; 10];
+        for position in &self.positions {
+            counts[position.depth] += 1;
+        }
+        counts
+    }
+
+    pub fn terminal_positions(&self) -> usize {
+        self.positions.iter().filter(|p| p.terminal).count()
+    }
+
+    /// Number of positions by their value with best play.
+    pub fn positions_by_value(&self) -> Vec<(Value, usize)> {
+        [Value::FirstMoverWins, Value::Draw, Value::SecondMoverWins]
+            .into_iter()
+            .map(|value| {
+                let count = self.positions.iter().filter(|p| p.value == value).count();
+                (value, count)
+            })
+            .collect()
+    }
+
+    /// Positions where the exact minimax of the engine gives another result than the tree,
+    /// described one per line; empty when they all agree.
+    pub fn check_minimax(&self) -> Vec<String> {
+        let mut minimax = Minimax::new();
+        let mut disagreements = Vec::new();
+        for position in &self.positions {
+            let Some(turn) = position.to_move() else {
+                continue;
+            };
+            let score = minimax.evaluate(&position.board, turn);
+            if score.signum() != position.value.score_for(turn) {
+                disagreements.push(format!(
+                    "{} ({} to move): the tree says {}, minimax scores {}",
+                    position.board_text(),
+                    if turn == FIRST_MOVER { 'x' } else { 'o' },
+                    position.value.name(),
+                    score
+                ));
+            }
+        }
+        disagreements
+    }
+
+    /// The report as JSON: the totals, then every position.
+    pub fn to_json(&self) -> String {
+        let games = self.games();
+        let per_depth: Vec<String> = self
+            .positions_per_depth()
+            .iter()
+            .map(usize::to_string)
+            .collect();
+        let by_value: Vec<String> = self
+            .positions_by_value()
+            .iter()
+            .map(|(value, count)| format!("\"{}\": {}", value.name(), count))
+            .collect();
+
+        let mut json = String::from("{\n");
+        json.push_str("  \"variant\": \"3×3\",\n");
+        json.push_str(&format!("  \"games\": {},\n", games.total()));
+        json.push_str(&format!(
+            "  \"games_by_result\": {{\"first_mover_wins\": {}, \"second_mover_wins\": {}, \"draws\": {}}},\n",
+            games.first_mover_wins, games.second_mover_wins, games.draws
+        ));
+        json.push_str(&format!("  \"positions\": {},\n", self.positions.len()));
+        json.push_str(&format!(
+            "  \"terminal_positions\": {},\n",
+            self.terminal_positions()
+        ));
+        json.push_str(&format!(
+            "  \"positions_per_depth\": [{}],\n",
+            per_depth.join(", ")
+        ));
+        json.push_str(&format!(
+            "  \"positions_by_value\": {{{}}},\n",
+            by_value.join(", ")
+        ));
+        json.push_str("  \"position_values\": [\n");
+        let lines: Vec<String> = self
+            .positions
+            .iter()
+            .map(|position| {
+                let to_move = match position.to_move() {
+                    Some(FIRST_MOVER) => "\"x\"",
+                    Some(_) => "\"o\"",
+                    None => "null",
+                };
+                format!(
+                    "    {{\"board\": \"{}\", \"depth\": {}, \"to_move\": {}, \"value\": \"{}\", \"games\": {}, \"first_mover_wins\": {}, \"second_mover_wins\": {}, \"draws\": {}}}",
+                    position.board_text(),
+                    position.depth,
+                    to_move,
+                    position.value.name(),
+                    position.games.total(),
+                    position.games.first_mover_wins,
+                    position.games.second_mover_wins,
+                    position.games.draws
+                )
+            })
+            .collect();
+        json.push_str(&lines.join(",\n"));
+        json.push_str("\n  ]\n}\n");
+        json
+    }
+
+    /// The report as CSV, one row per position. The totals are the row of the empty board.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(
+            "board,depth,to_move,value,games,first_mover_wins,second_mover_wins,draws\n",
+        );
+        for position in &self.positions {
+            let to_move = match position.to_move() {
+                Some(FIRST_MOVER) => "x",
+                Some(_) => "o",
+                None => "",
+            };
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{}\n",
+                position.board_text(),
+                position.depth,
+                to_move,
+                position.value.name(),
+                position.games.total(),
+                position.games.first_mover_wins,
+                position.games.second_mover_wins,
+                position.games.draws
+            ));
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_match_the_known_totals() {
+        let tree = GameTree::build();
+        let games = tree.games();
+        assert_eq!(games.total(), 255168);
+        assert_eq!(
+            (games.first_mover_wins, games.second_mover_wins, games.draws),
+            (131184, 77904, 46080)
+        );
+        assert_eq!(tree.positions.len(), 5478);
+        assert_eq!(tree.terminal_positions(), 958);
+        assert_eq!(
+            tree.positions_per_depth(),
+            vec![1, 9, 72, 252, 756, 1260, 1520, 1140, 390, 78]
+        );
+    }
+
+    #[test]
+    fn minimax_agrees_with_the_tree() {
+        let tree = GameTree::build();
+        assert_eq!(tree.check_minimax(), Vec::<String>::new());
+        assert_eq!(tree.positions[0].value, Value::Draw);
+    }
+}
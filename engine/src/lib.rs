@@ -0,0 +1,24 @@
+//! The tic-tac-toe engine, independent of any user interface: the rules of every
+//! variant, the strategies that pick moves, and the analysis behind hints.
+//!
+//! Positions are `Game` trait objects started from a `Variant` and advanced with `Move`s;
+//! a `Strategy` takes a position and returns the move it plays.
+
+pub mod analysis;
+pub mod game;
+pub mod game_tree;
+pub mod minimax;
+mod mnk;
+pub mod mode;
+mod notakto;
+pub mod notation;
+pub mod protocol;
+pub mod record;
+pub mod rules;
+pub mod search;
+pub mod selfplay;
+pub mod stats;
+pub mod strategy;
+mod ultimate;
+mod wild;
+pub mod win_graph;
@@ -0,0 +1,161 @@
+use crate::rules::{Board, Player};
+use std::collections::HashMap;
+
+/// Score bound for the alpha-beta window; larger than any reachable score.
+const INFINITY: i32 = 100;
+
+/// How a stored score relates to the true value of the position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+/// A transposition table entry: score from the side to move's point of view.
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    score: i32,
+    bound: Bound,
+}
+
+/// Full game-tree solver (negamax with alpha-beta pruning and a transposition table).
+///
+/// Scores are always from the point of view of the player to move:
+/// a win is worth `1 + empty cells left`, so faster wins and slower losses are preferred,
+/// and a draw is worth 0. Because the score only depends on the board itself,
+/// entries are keyed by the canonical board and shared between every path that reaches
+/// the same position or one of its rotations/reflections.
+#[derive(Default)]
+pub struct Minimax {
+    table: HashMap<(Board, Player), Entry>,
+}
+
+impl Minimax {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns every move that is optimal for `turn` on `board`.
+    /// Empty if the game is already over.
+    pub fn best_moves(&mut self, board: &Board, turn: Player) -> Vec<usize> {
+        self.root_moves(board, turn, None)
+    }
+
+    /// Game-theoretic value of `board` with `turn` to move:
+    /// positive if `turn` can force a win, 0 for a draw, negative if `turn` loses.
+    pub fn evaluate(&mut self, board: &Board, turn: Player) -> i32 {
+        self.negamax(board, turn, -INFINITY, INFINITY)
+    }
+
+    /// Like `best_moves`, but only looks `depth` plies ahead (the root move included).
+    /// Positions beyond the horizon are scored as a draw, so deeper threats go unnoticed.
+    pub fn best_moves_to_depth(&mut self, board: &Board, turn: Player, depth: usize) -> Vec<usize> {
+        self.root_moves(board, turn, Some(depth.max(1)))
+    }
+
+    fn root_moves(&mut self, board: &Board, turn: Player, depth: Option<usize>) -> Vec<usize> {
+        if board.winner().is_some() {
+            return Vec::new();
+        }
+
+        let mut best_score = -INFINITY;
+        let mut best = Vec::new();
+        for idx in board.empty_cells() {
+            let child = board.with(idx, turn);
+            let score = match depth {
+                None => -self.negamax(&child, turn.opponent(), -INFINITY, INFINITY),
+                Some(depth) => {
+                    -Self::negamax_limited(&child, turn.opponent(), depth - 1, -INFINITY, INFINITY)
+                }
+            };
+
+            if score > best_score {
+                best_score = score;
+                best.clear();
+            }
+            if score == best_score {
+                best.push(idx);
+            }
+        }
+        best
+    }
+
+    /// Plain alpha-beta without the transposition table: its scores depend on the
+    /// remaining depth, so they can't be shared with the exact ones.
+    fn negamax_limited(
+        board: &Board,
+        turn: Player,
+        depth: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        let empty = board.empty_count() as i32;
+        if board.winner().is_some() {
+            return -(1 + empty);
+        }
+        if empty == 0 || depth == 0 {
+            return 0;
+        }
+
+        let mut best = -INFINITY;
+        for idx in board.empty_cells() {
+            let child = board.with(idx, turn);
+            let score = -Self::negamax_limited(&child, turn.opponent(), depth - 1, -beta, -alpha);
+
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+
+    fn negamax(&mut self, board: &Board, turn: Player, mut alpha: i32, mut beta: i32) -> i32 {
+        let empty = board.empty_count() as i32;
+
+        // The previous move can only have won for the opponent
+        if board.winner().is_some() {
+            return -(1 + empty);
+        }
+        if empty == 0 {
+            return 0;
+        }
+
+        let (key, _) = board.canonical();
+        let alpha_orig = alpha;
+        if let Some(entry) = self.table.get(&(key, turn)) {
+            match entry.bound {
+                Bound::Exact => return entry.score,
+                Bound::Lower => alpha = alpha.max(entry.score),
+                Bound::Upper => beta = beta.min(entry.score),
+            }
+            if alpha >= beta {
+                return entry.score;
+            }
+        }
+
+        let mut best = -INFINITY;
+        for idx in board.empty_cells() {
+            let child = board.with(idx, turn);
+            let score = -self.negamax(&child, turn.opponent(), -beta, -alpha);
+
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= alpha_orig {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert((key, turn), Entry { score: best, bound });
+        best
+    }
+}
@@ -0,0 +1,387 @@
+//! m,n,k games: `k` in a row wins on a `width`×`height` board.
+//! Classic tic-tac-toe is the 3,3,3 game; gomoku is 15,15,5.
+//! In the misère form, completing `k` in a row loses instead.
+//!
+//! Boards may also be stacked into `layers`, for 3D tic-tac-toe: lines then run through
+//! the layers too, and the layers are drawn side by side.
+//! Cell `idx` is at `x = idx % width`, `y = idx / width % height`, `z = idx / (width * height)`.
+
+use crate::game::Game;
+use crate::rules::{Board, Move, Outcome, Player};
+use std::rc::Rc;
+
+/// Boards up to this many cells search every empty cell; larger ones only search
+/// cells near the stones already played.
+const FULL_SEARCH_CELLS: usize = 25;
+
+/// How far from the nearest stone a candidate move may be, on large boards.
+const CANDIDATE_DISTANCE: usize = 2;
+
+/// Gap between the layers of a 3D board, in tile units.
+const LAYER_GAP: f32 = 0.5;
+
+/// Every run of `k` cells in a straight line through a `width`×`height`×`layers` board:
+/// rows, columns and diagonals of each layer, and the lines crossing the layers.
+/// That is 8 lines on the classic board, 49 on the 3×3×3 cube and 76 on the 4×4×4 one.
+pub fn win_lines(width: usize, height: usize, layers: usize, k: usize) -> Vec<Vec<usize>> {
+    let mut lines = Vec::new();
+    if k == 0 {
+        return lines;
+    }
+    // One direction out of each opposite pair: the first non-zero step is positive
+    let directions: Vec<[isize; 3]> = (-1..=1)
+        .flat_map(|dz| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dx| [dx, dy, dz])))
+        .filter(|step| step.iter().find(|&&d| d != 0) == Some(&1))
+        .collect();
+    let size = [width as isize, height as isize, layers as isize];
+    let index = |at: [isize; 3]| (at[2] * size[1] * size[0] + at[1] * size[0] + at[0]) as usize;
+
+    for z in 0..size[2] {
+        for y in 0..size[1] {
+            for x in 0..size[0] {
+                for step in &directions {
+                    let start = [x, y, z];
+                    let end: Vec<isize> = (0..3)
+                        .map(|a| start[a] + step[a] * (k as isize - 1))
+                        .collect();
+                    if (0..3).any(|a| !(0..size[a]).contains(&end[a])) {
+                        continue;
+                    }
+                    let line = (0..k as isize)
+                        .map(|n| index([x + step[0] * n, y + step[1] * n, z + step[2] * n]))
+                        .collect();
+                    lines.push(line);
+                }
+            }
+        }
+    }
+    lines
+}
+
+/// The win lines of one board configuration, and the lines through each cell.
+/// Shared by every copy of a game made during the search.
+pub struct Lines {
+    pub lines: Vec<Vec<usize>>,
+    /// `through[cell]` lists the indices of the lines that contain `cell`.
+    pub through: Vec<Vec<usize>>,
+}
+
+impl Lines {
+    pub fn new(width: usize, height: usize, layers: usize, k: usize) -> Self {
+        let lines = win_lines(width, height, layers, k);
+        let mut through = vec![Vec::new(); width * height * layers];
+        for (index, line) in lines.iter().enumerate() {
+            for &cell in line {
+                through[cell].push(index);
+            }
+        }
+        Lines { lines, through }
+    }
+}
+
+/// Heuristic value of a line of `k` holding `stones` of one kind and nothing that blocks it.
+/// Grows steeply, so one line close to completion outweighs many open ones.
+pub fn line_value(k: usize, stones: u8) -> i32 {
+    if stones == 0 {
+        0
+    } else {
+        1 << (3 * stones.min(k as u8 - 1).min(9) as u32)
+    }
+}
+
+#[derive(Clone)]
+pub struct MnkGame {
+    width: usize,
+    height: usize,
+    layers: usize,
+    k: usize,
+    /// Completing a line loses instead of winning.
+    misere: bool,
+    lines: Rc<Lines>,
+    cells: Vec<Option<Player>>,
+    /// Stones of each side on every line: `[machine, human]`.
+    counts: Vec<[u8; 2]>,
+    empty: usize,
+    to_move: Player,
+    /// The side that won by a completed line, and that line's index.
+    winner: Option<(Player, usize)>,
+}
+
+fn side(player: Player) -> usize {
+    match player {
+        Player::Machine => 0,
+        Player::Human => 1,
+    }
+}
+
+impl MnkGame {
+    pub fn new(width: usize, height: usize, k: usize, first_player: Player) -> Self {
+        Self::layered(width, height, 1, k, first_player)
+    }
+
+    /// 3D tic-tac-toe on a `size`×`size`×`size` cube, `size` in a row wins.
+    pub fn cube(size: usize, first_player: Player) -> Self {
+        Self::layered(size, size, size, size, first_player)
+    }
+
+    fn layered(width: usize, height: usize, layers: usize, k: usize, first_player: Player) -> Self {
+        let cells = width * height * layers;
+        let lines = Lines::new(width, height, layers, k);
+        MnkGame {
+            width,
+            height,
+            layers,
+            k,
+            misere: false,
+            counts: vec![[0, 0]; lines.lines.len()],
+            lines: Rc::new(lines),
+            cells: vec![None; cells],
+            empty: cells,
+            to_move: first_player,
+            winner: None,
+        }
+    }
+
+    /// The misère form of this game: whoever completes a line loses.
+    pub fn misere(mut self) -> Self {
+        self.misere = true;
+        self
+    }
+
+    fn is_classic(&self) -> bool {
+        self.width == 3 && self.height == 3 && self.layers == 1 && self.k == 3 && !self.misere
+    }
+
+    /// The `[x, y, z]` coordinates of cell `idx`.
+    fn coords(&self, idx: usize) -> [usize; 3] {
+        [
+            idx % self.width,
+            idx / self.width % self.height,
+            idx / (self.width * self.height),
+        ]
+    }
+
+    fn index(&self, [x, y, z]: [usize; 3]) -> usize {
+        (z * self.height + y) * self.width + x
+    }
+
+    /// Moves on the middle cell(s) of the board: one per axis of odd length, two per even one.
+    fn center_cells(&self) -> Vec<Move> {
+        let middle = |size: usize| {
+            let mut middle = vec![(size - 1) / 2, size / 2];
+            middle.dedup();
+            middle
+        };
+        let mut cells = Vec::new();
+        for z in middle(self.layers) {
+            for y in middle(self.height) {
+                for x in middle(self.width) {
+                    cells.push(Move::new(self.index([x, y, z]), self.to_move));
+                }
+            }
+        }
+        cells
+    }
+
+    /// Value of a line holding `stones` of one side and none of the other.
+    fn line_value(&self, stones: u8) -> i32 {
+        line_value(self.k, stones)
+    }
+
+    /// How much taking `idx` would extend the side to move's lines or block the opponent's;
+    /// used to try strong moves first.
+    fn potential(&self, idx: usize) -> i32 {
+        let me = side(self.to_move);
+        self.lines.through[idx]
+            .iter()
+            .map(|&line| {
+                let count = self.counts[line];
+                let (mine, theirs) = (count[me], count[1 - me]);
+                match (mine, theirs) {
+                    (0, 0) => 1,
+                    (_, 0) => 2 * self.line_value(mine),
+                    (0, _) => self.line_value(theirs),
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+
+    /// Empty cells within `CANDIDATE_DISTANCE` of a stone (along every axis),
+    /// or the center on an empty board.
+    fn nearby_cells(&self) -> Vec<Move> {
+        if self.empty == self.cells.len() {
+            return self.center_cells();
+        }
+        let stones: Vec<[usize; 3]> = (0..self.cells.len())
+            .filter(|&idx| self.cells[idx].is_some())
+            .map(|idx| self.coords(idx))
+            .collect();
+        let near_stone = |idx: usize| {
+            let at = self.coords(idx);
+            stones
+                .iter()
+                .any(|stone| (0..3).all(|a| at[a].abs_diff(stone[a]) <= CANDIDATE_DISTANCE))
+        };
+        self.legal_moves()
+            .into_iter()
+            .filter(|mv| near_stone(mv.idx))
+            .collect()
+    }
+}
+
+impl Game for MnkGame {
+    fn cell_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn cell_position(&self, idx: usize) -> (f32, f32) {
+        let [x, y, z] = self.coords(idx);
+        (
+            z as f32 * (self.width as f32 + LAYER_GAP) + x as f32,
+            y as f32,
+        )
+    }
+
+    fn grid_size(&self) -> (f32, f32) {
+        let layers = self.layers as f32;
+        (
+            layers * self.width as f32 + (layers - 1.0) * LAYER_GAP,
+            self.height as f32,
+        )
+    }
+
+    fn cell(&self, idx: usize) -> Option<Player> {
+        self.cells.get(idx).copied().flatten()
+    }
+
+    fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        if self.winner.is_some() {
+            return Vec::new();
+        }
+        (0..self.cells.len())
+            .filter(|&idx| self.cells[idx].is_none())
+            .map(|idx| Move::new(idx, self.to_move))
+            .collect()
+    }
+
+    fn candidate_moves(&self) -> Vec<Move> {
+        let mut moves = if self.cells.len() > FULL_SEARCH_CELLS {
+            self.nearby_cells()
+        } else {
+            self.legal_moves()
+        };
+        moves.sort_by_cached_key(|mv| -self.potential(mv.idx));
+        moves
+    }
+
+    /// The center or the top-left corner on the classic board, as the Machine always
+    /// opened there; the center cell(s) elsewhere.
+    fn opening_moves(&self) -> Vec<Move> {
+        if self.is_classic() {
+            return vec![Move::new(4, self.to_move), Move::new(0, self.to_move)];
+        }
+        self.center_cells()
+    }
+
+    fn play(&mut self, mv: Move) {
+        let idx = mv.idx;
+        if self.winner.is_some() || self.cells.get(idx) != Some(&None) {
+            return;
+        }
+        let player = self.to_move;
+        self.cells[idx] = Some(player);
+        self.empty -= 1;
+        for &line in &self.lines.through[idx] {
+            let count = &mut self.counts[line];
+            count[side(player)] += 1;
+            if count[side(player)] as usize == self.k && self.winner.is_none() {
+                let winner = if self.misere {
+                    player.opponent()
+                } else {
+                    player
+                };
+                self.winner = Some((winner, line));
+            }
+        }
+        self.to_move = player.opponent();
+    }
+
+    fn outcome(&self) -> Outcome {
+        match self.winner {
+            Some((player, line)) => Outcome::Win(player, self.lines.lines[line].clone()),
+            None if self.empty == 0 => Outcome::Draw,
+            None => Outcome::InProgress,
+        }
+    }
+
+    /// Sums every line still open to exactly one side, so rows close to completion count most;
+    /// in misère those rows count against their owner.
+    fn evaluate(&self, player: Player) -> i32 {
+        let me = side(player);
+        let score: i32 = self
+            .counts
+            .iter()
+            .map(|count| match (count[me], count[1 - me]) {
+                (mine, 0) => self.line_value(mine),
+                (0, theirs) => -self.line_value(theirs),
+                _ => 0,
+            })
+            .sum();
+        if self.misere {
+            -score
+        } else {
+            score
+        }
+    }
+
+    fn classic_board(&self) -> Option<Board> {
+        if !self.is_classic() {
+            return None;
+        }
+        let mut board = Board::new();
+        for (idx, cell) in self.cells.iter().enumerate() {
+            board.set(idx, *cell);
+        }
+        Some(board)
+    }
+
+    fn clone_box(&self) -> Box<dyn Game> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn win_line_counts() {
+        assert_eq!(win_lines(3, 3, 1, 3).len(), 8);
+        assert_eq!(win_lines(3, 3, 3, 3).len(), 49);
+        assert_eq!(win_lines(4, 4, 4, 4).len(), 76);
+    }
+
+    #[test]
+    fn completing_a_line_wins() {
+        let mut game = MnkGame::new(3, 3, 3, Player::Machine);
+        for idx in [0, 3, 1, 4, 2] {
+            game.play(Move::new(idx, game.to_move()));
+        }
+        assert_eq!(game.outcome(), Outcome::Win(Player::Machine, vec![0, 1, 2]));
+        assert!(game.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn misere_completing_a_line_loses() {
+        let mut game = MnkGame::new(3, 3, 3, Player::Machine).misere();
+        for idx in [0, 3, 1, 4, 2] {
+            game.play(Move::new(idx, game.to_move()));
+        }
+        assert_eq!(game.outcome(), Outcome::Win(Player::Human, vec![0, 1, 2]));
+    }
+}
@@ -0,0 +1,86 @@
+//! Who plays the two sides of a game, and how the front-ends refer to them.
+
+use crate::rules::{Outcome, Player};
+
+/// Who plays the two sides; the index matches the mode selector of the window.
+/// In `Hotseat` the `Player::Machine` side is played by a second person,
+/// in `SelfPlay` both sides are played by engine strategies.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    VsMachine,
+    Hotseat,
+    SelfPlay,
+}
+
+impl GameMode {
+    /// Maps the selector index from the UI; unknown indices fall back to the default.
+    pub fn from_index(index: i32) -> Self {
+        match index {
+            1 => GameMode::Hotseat,
+            2 => GameMode::SelfPlay,
+            _ => GameMode::VsMachine,
+        }
+    }
+
+    /// True if `player`'s moves come from a person rather than from the engine.
+    pub fn is_person(self, player: Player) -> bool {
+        match self {
+            GameMode::VsMachine => player == Player::Human,
+            GameMode::Hotseat => true,
+            GameMode::SelfPlay => false,
+        }
+    }
+
+    /// How the banner refers to a side in this mode.
+    pub fn side_name(self, player: Player) -> &'static str {
+        match (self, player) {
+            (GameMode::VsMachine, Player::Human) => "You",
+            (GameMode::VsMachine, Player::Machine) => "Machine",
+            (_, Player::Human) => "Player 1",
+            (_, Player::Machine) => "Player 2",
+        }
+    }
+
+    /// Who plays `player`'s side, for game records: the side's name for a person,
+    /// otherwise the name of the strategy playing it, `machine` for the Machine side
+    /// and `opponent` for the Human side.
+    pub fn player_name(self, player: Player, machine: &str, opponent: &str) -> String {
+        if self.is_person(player) {
+            return self.side_name(player).to_string();
+        }
+        match player {
+            Player::Human => opponent.to_string(),
+            Player::Machine => machine.to_string(),
+        }
+    }
+
+    /// Text for the banner: the result once the game is over, otherwise whose turn it is
+    /// (not shown when playing against the Machine).
+    pub fn status_text(self, outcome: &Outcome, to_move: Player) -> String {
+        match outcome {
+            Outcome::InProgress if self != GameMode::VsMachine => {
+                format!("{} to move", self.side_name(to_move))
+            }
+            Outcome::InProgress => String::new(),
+            Outcome::Win(Player::Human, _) if self == GameMode::VsMachine => "You win".to_string(),
+            Outcome::Win(player, _) => format!("{} wins", self.side_name(*player)),
+            Outcome::Draw => "Draw".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn player_names_name_people_by_side_and_engines_by_strategy() {
+        let names = |mode: GameMode| {
+            [Player::Human, Player::Machine].map(|player| mode.player_name(player, "Hard", "Easy"))
+        };
+        assert_eq!(names(GameMode::VsMachine), ["You", "Hard"]);
+        assert_eq!(names(GameMode::Hotseat), ["Player 1", "Player 2"]);
+        assert_eq!(names(GameMode::SelfPlay), ["Easy", "Hard"]);
+    }
+}
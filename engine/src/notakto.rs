@@ -0,0 +1,189 @@
+//! Notakto: both sides place X on one or more 3×3 boards. A board with three in a row
+//! is dead and takes no more moves; whoever kills the last live board loses.
+//!
+//! Cell `idx` is cell `idx % 9` of board `idx / 9`, numbered like the classic board.
+
+use crate::game::{Game, Marks};
+use crate::rules::{Board, Move, Outcome, Player};
+
+/// Gap between the boards, in tile units.
+const BOARD_GAP: f32 = 0.5;
+
+#[derive(Clone)]
+pub struct NotaktoGame {
+    /// The X's on every board; all of them are stored as one side, since lines don't
+    /// care who placed them.
+    boards: Vec<Board>,
+    /// Who placed each mark, for the UI and the move record.
+    placed_by: Vec<Option<Player>>,
+    /// Who killed each dead board.
+    killed_by: Vec<Option<Player>>,
+    /// The board of the last move.
+    last_board: Option<usize>,
+    to_move: Player,
+}
+
+impl NotaktoGame {
+    pub fn new(boards: usize, first_player: Player) -> Self {
+        NotaktoGame {
+            boards: vec![Board::new(); boards.max(1)],
+            placed_by: vec![None; 9 * boards.max(1)],
+            killed_by: vec![None; boards.max(1)],
+            last_board: None,
+            to_move: first_player,
+        }
+    }
+
+    fn is_dead(&self, board: usize) -> bool {
+        self.boards[board].winner().is_some()
+    }
+
+    /// Free cells of live boards that don't complete a line; once none are left,
+    /// the side to move must kill a board.
+    fn safe_moves(&self) -> usize {
+        self.legal_moves()
+            .into_iter()
+            .filter(|mv| {
+                let (board, cell) = (mv.idx / 9, mv.idx % 9);
+                self.boards[board]
+                    .with(cell, Player::Machine)
+                    .winner()
+                    .is_none()
+            })
+            .count()
+    }
+
+    /// The line that killed `board`, if it is dead.
+    fn killing_line(&self, board: usize) -> Option<Vec<usize>> {
+        let (_, line) = self.boards[board].winning_line()?;
+        Some(line.iter().map(|cell| board * 9 + cell).collect())
+    }
+}
+
+impl Game for NotaktoGame {
+    fn cell_count(&self) -> usize {
+        self.placed_by.len()
+    }
+
+    fn cell_position(&self, idx: usize) -> (f32, f32) {
+        let (board, cell) = (idx / 9, idx % 9);
+        (
+            board as f32 * (3.0 + BOARD_GAP) + (cell % 3) as f32,
+            (cell / 3) as f32,
+        )
+    }
+
+    fn grid_size(&self) -> (f32, f32) {
+        let boards = self.boards.len() as f32;
+        (boards * 3.0 + (boards - 1.0) * BOARD_GAP, 3.0)
+    }
+
+    fn cell(&self, idx: usize) -> Option<Player> {
+        self.placed_by.get(idx).copied().flatten()
+    }
+
+    fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    fn marks(&self) -> Marks {
+        Marks::AllX
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        (0..self.boards.len())
+            .filter(|&board| !self.is_dead(board))
+            .flat_map(|board| {
+                self.boards[board]
+                    .empty_cells()
+                    .map(move |cell| board * 9 + cell)
+            })
+            .map(|idx| Move::new(idx, self.to_move))
+            .collect()
+    }
+
+    /// The center of the first board; on a single board that wins outright.
+    fn opening_moves(&self) -> Vec<Move> {
+        vec![Move::new(4, self.to_move)]
+    }
+
+    fn play(&mut self, mv: Move) {
+        let (idx, board, cell) = (mv.idx, mv.idx / 9, mv.idx % 9);
+        if board >= self.boards.len() || self.is_dead(board) || !self.boards[board].is_free(cell) {
+            return;
+        }
+        self.boards[board].set(cell, Some(Player::Machine));
+        self.placed_by[idx] = Some(self.to_move);
+        if self.is_dead(board) {
+            self.killed_by[board] = Some(self.to_move);
+        }
+        self.last_board = Some(board);
+        self.to_move = self.to_move.opponent();
+    }
+
+    /// Once every board is dead, the side to move wins: the other side killed the last one.
+    /// The line that killed it is highlighted.
+    fn outcome(&self) -> Outcome {
+        if !(0..self.boards.len()).all(|board| self.is_dead(board)) {
+            return Outcome::InProgress;
+        }
+        let line = self
+            .last_board
+            .and_then(|board| self.killing_line(board))
+            .unwrap_or_default();
+        Outcome::Win(self.to_move, line)
+    }
+
+    /// Only the parity of the safe moves left is judged: whoever makes the last safe move
+    /// leaves the opponent to kill a board. A rough guide, since every move can also take
+    /// away other safe moves.
+    fn evaluate(&self, player: Player) -> i32 {
+        let score = if self.safe_moves() % 2 == 1 { 1 } else { -1 };
+        if player == self.to_move {
+            score
+        } else {
+            -score
+        }
+    }
+
+    /// The line that killed each dead board, with the side that completed it.
+    fn decided_lines(&self) -> Vec<(Player, Vec<usize>)> {
+        (0..self.boards.len())
+            .filter_map(|board| Some((self.killed_by[board]?, self.killing_line(board)?)))
+            .collect()
+    }
+
+    fn clone_box(&self) -> Box<dyn Game> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn killing_the_last_board_loses() {
+        let mut game = NotaktoGame::new(1, Player::Machine);
+        for idx in [0, 1] {
+            game.play(Move::new(idx, game.to_move()));
+            assert_eq!(game.outcome(), Outcome::InProgress);
+        }
+        // The Machine completes the top row and kills the only board
+        game.play(Move::new(2, Player::Machine));
+        assert_eq!(game.outcome(), Outcome::Win(Player::Human, vec![0, 1, 2]));
+        assert!(game.legal_moves().is_empty());
+    }
+
+    #[test]
+    fn dead_boards_take_no_moves() {
+        let mut game = NotaktoGame::new(3, Player::Machine);
+        for idx in [0, 1, 2] {
+            game.play(Move::new(idx, game.to_move()));
+        }
+        assert_eq!(game.outcome(), Outcome::InProgress);
+        assert!(game.legal_moves().iter().all(|mv| mv.idx >= 9));
+        assert_eq!(game.legal_moves().len(), 18);
+        assert_eq!(game.decided_lines(), vec![(Player::Machine, vec![0, 1, 2])]);
+    }
+}
@@ -0,0 +1,213 @@
+//! Names for cells and moves. Besides the cell index of `Move`'s own notation (`M4`),
+//! cells have algebraic coordinates like `b2`: a column letter and a row number,
+//! counted over the tiles as they are drawn, so sub-boards and layers simply
+//! continue the lettering (the center of ultimate tic-tac-toe is `e5`).
+
+use crate::game::Game;
+use crate::rules::{Mark, Move, Player};
+
+/// How moves are written in records.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Notation {
+    /// Player letter and cell index: `M4`, `H4O`.
+    Index,
+    /// Player letter and coordinates: `Mb2`, `Hb2O`.
+    #[default]
+    Algebraic,
+}
+
+/// Tolerance when comparing tile positions.
+const EPSILON: f32 = 0.01;
+
+/// The columns and rows of a variant's drawing, and where each cell sits in them.
+pub struct Coordinates {
+    columns: Vec<f32>,
+    rows: Vec<f32>,
+    /// `grid[row][column]` is the cell drawn there, if any.
+    grid: Vec<Vec<Option<usize>>>,
+    /// `at[idx]` is the `(column, row)` of cell `idx`.
+    at: Vec<(usize, usize)>,
+}
+
+impl Coordinates {
+    pub fn new(game: &dyn Game) -> Self {
+        let positions: Vec<(f32, f32)> = (0..game.cell_count())
+            .map(|idx| game.cell_position(idx))
+            .collect();
+        let distinct = |mut values: Vec<f32>| {
+            values.sort_by(f32::total_cmp);
+            values.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+            values
+        };
+        let columns = distinct(positions.iter().map(|p| p.0).collect());
+        let rows = distinct(positions.iter().map(|p| p.1).collect());
+        let find = |values: &[f32], value: f32| {
+            values
+                .iter()
+                .position(|v| (v - value).abs() < EPSILON)
+                .unwrap_or(0)
+        };
+
+        let mut grid = vec![vec![None; columns.len()]; rows.len()];
+        let at: Vec<(usize, usize)> = positions
+            .iter()
+            .map(|&(x, y)| (find(&columns, x), find(&rows, y)))
+            .collect();
+        for (idx, &(column, row)) in at.iter().enumerate() {
+            grid[row][column] = Some(idx);
+        }
+        Coordinates {
+            columns,
+            rows,
+            grid,
+            at,
+        }
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// True if the drawing leaves a gap before `column`, e.g. between sub-boards.
+    pub fn gap_before_column(&self, column: usize) -> bool {
+        column > 0 && self.columns[column] - self.columns[column - 1] > 1.0 + EPSILON
+    }
+
+    /// True if the drawing leaves a gap before `row`.
+    pub fn gap_before_row(&self, row: usize) -> bool {
+        row > 0 && self.rows[row] - self.rows[row - 1] > 1.0 + EPSILON
+    }
+
+    /// The letter of `column`: `a` for the leftmost one.
+    pub fn column_letter(column: usize) -> char {
+        (b'a' + column as u8) as char
+    }
+
+    /// The cell drawn at `column` and `row`, if any.
+    pub fn cell_at(&self, column: usize, row: usize) -> Option<usize> {
+        self.grid.get(row)?.get(column).copied().flatten()
+    }
+
+    /// Coordinates of cell `idx`, like `b2`.
+    pub fn name(&self, idx: usize) -> String {
+        let (column, row) = self.at[idx];
+        format!("{}{}", Self::column_letter(column), row + 1)
+    }
+
+    /// The cell named by coordinates like `b2`; `None` if nothing is drawn there.
+    pub fn cell(&self, name: &str) -> Option<usize> {
+        let mut chars = name.chars();
+        let column = chars.next().filter(char::is_ascii_lowercase)?;
+        let row: usize = chars.as_str().parse().ok()?;
+        self.cell_at((column as u8 - b'a') as usize, row.checked_sub(1)?)
+    }
+
+    /// `mv` in `notation`; the player letter comes first and the mark, if any, last.
+    pub fn format_move(&self, mv: &Move, notation: Notation) -> String {
+        match notation {
+            Notation::Index => mv.to_string(),
+            Notation::Algebraic => format!(
+                "{}{}{}",
+                mv.player.letter(),
+                self.name(mv.idx),
+                mv.mark.map_or("", Mark::letter)
+            ),
+        }
+    }
+
+    /// Reads a move in either notation: `M4`, `Mb2`, `H4O` or `Hb2O`.
+    pub fn parse_move(&self, text: &str) -> Option<Move> {
+        let rest = text.get(1..)?;
+        if rest.starts_with(|c: char| c.is_ascii_digit()) {
+            return Move::from_notation(text);
+        }
+        let player = Player::from_letter(text.get(..1)?)?;
+        let (cell, mark) = match rest.strip_suffix(['X', 'O']) {
+            Some(cell) => (cell, Mark::from_letter(&rest[cell.len()..])),
+            None => (rest, None),
+        };
+        let mv = Move::new(self.cell(cell)?, player);
+        Some(match mark {
+            Some(mark) => mv.with_mark(mark),
+            None => mv,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Variant;
+
+    fn coordinates(variant: Variant) -> Coordinates {
+        Coordinates::new(variant.new_game(Player::Human).as_ref())
+    }
+
+    #[test]
+    fn names_follow_the_drawing() {
+        let classic = coordinates(Variant::default());
+        assert_eq!((classic.columns(), classic.rows()), (3, 3));
+        assert_eq!(classic.name(0), "a1");
+        assert_eq!(classic.name(4), "b2");
+        assert_eq!(classic.name(8), "c3");
+
+        let ultimate = coordinates(Variant::Ultimate);
+        assert_eq!((ultimate.columns(), ultimate.rows()), (9, 9));
+        assert_eq!(ultimate.name(40), "e5");
+        assert_eq!(ultimate.name(9), "d1");
+        assert!(ultimate.gap_before_column(3) && !ultimate.gap_before_column(2));
+        assert!(ultimate.gap_before_row(6));
+
+        // The boards of Notakto sit side by side
+        let notakto = coordinates(Variant::Notakto { boards: 3 });
+        assert_eq!((notakto.columns(), notakto.rows()), (9, 3));
+        assert_eq!(notakto.name(9), "d1");
+        assert_eq!(notakto.name(26), "i3");
+    }
+
+    #[test]
+    fn moves_round_trip_in_both_notations() {
+        for variant in [
+            Variant::default(),
+            Variant::Ultimate,
+            Variant::Notakto { boards: 3 },
+            Variant::Cube { size: 4 },
+        ] {
+            let coordinates = coordinates(variant);
+            let cells = variant.new_game(Player::Human).cell_count();
+            for idx in 0..cells {
+                let mv = Move::new(idx, Player::Machine);
+                for notation in [Notation::Index, Notation::Algebraic] {
+                    let text = coordinates.format_move(&mv, notation);
+                    assert_eq!(coordinates.parse_move(&text), Some(mv), "{}", text);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn marked_moves_round_trip() {
+        let coordinates = coordinates(Variant::Wild);
+        let mv = Move::new(5, Player::Human).with_mark(Mark::O);
+        assert_eq!(coordinates.format_move(&mv, Notation::Index), "H5O");
+        assert_eq!(coordinates.format_move(&mv, Notation::Algebraic), "Hc2O");
+        assert_eq!(coordinates.parse_move("H5O"), Some(mv));
+        assert_eq!(coordinates.parse_move("Hc2O"), Some(mv));
+        assert_eq!(
+            coordinates.parse_move("Ma1X"),
+            Some(Move::new(0, Player::Machine).with_mark(Mark::X))
+        );
+    }
+
+    #[test]
+    fn rejects_cells_off_the_board() {
+        let coordinates = coordinates(Variant::default());
+        for text in ["Md1", "Ma4", "Ma0", "M", "Xb2", "Mb", "MB2"] {
+            assert_eq!(coordinates.parse_move(text), None, "{:?}", text);
+        }
+    }
+}
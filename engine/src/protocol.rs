@@ -0,0 +1,385 @@
+//! A line-based text protocol for driving the engine from other programs, in the
+//! spirit of GTP and UCI. Every command is one line; every reply starts with `=` on
+//! success or `?` on failure and ends with an empty line. Blank lines and lines
+//! starting with `#` are ignored, so scripts can be piped in as they are.
+//!
+//! Moves are written as the player letter and the cell index, plus the mark where
+//! the player picks it: `M4`, `H0`, `H4O`.
+//!
+//! ```text
+//! newgame [variant]           empty board, optionally switching the variant
+//! position [startpos] [moves <move>...]
+//!                             the position after these moves from an empty board
+//! play <move>                 adds one move to the position
+//! go                          the move the engine plays; the position is unchanged
+//! analyze                     verdict for the side to move and every best move
+//! status                      in_progress <side> | win <side> <cells>... | draw
+//! setoption <name> <value>    difficulty <strategy> | variant <variant> | first M|H
+//! name, version, list_commands, quit
+//! ```
+
+use crate::analysis::{self, Verdict};
+use crate::game::{self, Game, Variant};
+use crate::rules::{Move, Outcome, Player};
+use crate::strategy::{self, Difficulty, Strategy};
+use std::fmt;
+
+const COMMANDS: [&str; 11] = [
+    "name",
+    "version",
+    "list_commands",
+    "newgame",
+    "position",
+    "play",
+    "go",
+    "analyze",
+    "status",
+    "setoption",
+    "quit",
+];
+
+/// The answer to one command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    Success(String),
+    Failure(String),
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (prefix, text) = match self {
+            Response::Success(text) => ("=", text),
+            Response::Failure(text) => ("?", text),
+        };
+        if text.is_empty() {
+            write!(f, "{}\n\n", prefix)
+        } else {
+            write!(f, "{} {}\n\n", prefix, text)
+        }
+    }
+}
+
+/// The state kept between commands: the variant, the moves played so far and the
+/// strategy that answers `go`.
+pub struct Session {
+    variant: Variant,
+    /// Who opens an empty board; a position given with moves opens with its first mover.
+    first_player: Player,
+    moves: Vec<Move>,
+    strategy: Box<dyn Strategy>,
+    quit: bool,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Session {
+            variant: Variant::default(),
+            first_player: Player::Human,
+            moves: Vec::new(),
+            strategy: Box::new(Difficulty::default()),
+            quit: false,
+        }
+    }
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True once `quit` was received.
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Runs one command line; `None` for blank lines and comments, which get no reply.
+    pub fn execute(&mut self, line: &str) -> Option<Response> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let args: Vec<&str> = words.collect();
+        Some(match self.run(command, &args) {
+            Ok(text) => Response::Success(text),
+            Err(message) => Response::Failure(message),
+        })
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "name" => Ok("ttt-engine".to_string()),
+            "version" => Ok(env!("CARGO_PKG_VERSION").to_string()),
+            "list_commands" => Ok(COMMANDS.join(" ")),
+            "quit" => {
+                self.quit = true;
+                Ok(String::new())
+            }
+            "newgame" => {
+                if !args.is_empty() {
+                    self.variant = parse_variant(&args.join(" "))?;
+                }
+                self.moves.clear();
+                Ok(String::new())
+            }
+            "position" => {
+                let args = match args.first() {
+                    Some(&"startpos") => &args[1..],
+                    _ => args,
+                };
+                let moves = match args.split_first() {
+                    None => &[][..],
+                    Some((&"moves", moves)) => moves,
+                    Some((word, _)) => return Err(format!("expected 'moves', got '{}'", word)),
+                };
+                let moves = moves
+                    .iter()
+                    .map(|text| parse_move(text))
+                    .collect::<Result<Vec<Move>, String>>()?;
+                self.check_moves(&moves)?;
+                self.moves = moves;
+                Ok(String::new())
+            }
+            "play" => {
+                let [text] = args else {
+                    return Err("usage: play <move>".to_string());
+                };
+                let mut moves = self.moves.clone();
+                moves.push(parse_move(text)?);
+                self.check_moves(&moves)?;
+                self.moves = moves;
+                Ok(String::new())
+            }
+            "go" => {
+                let game = self.game();
+                if game.outcome() != Outcome::InProgress {
+                    return Err("game over".to_string());
+                }
+                self.strategy
+                    .choose_move(game.as_ref())
+                    .map(|mv| mv.to_string())
+                    .ok_or_else(|| "no move found".to_string())
+            }
+            "analyze" => {
+                let game = self.game();
+                let analysis = analysis::analyze(game.as_ref());
+                if analysis.best_moves.is_empty() {
+                    return Err("game over".to_string());
+                }
+                let verdict = match analysis.verdict {
+                    Verdict::Win => "win",
+                    Verdict::Draw => "draw",
+                    Verdict::Loss => "loss",
+                    Verdict::Unclear => "unclear",
+                };
+                let best: Vec<String> = analysis.best_moves.iter().map(Move::to_string).collect();
+                Ok(format!("{} {}", verdict, best.join(" ")))
+            }
+            "status" => {
+                let game = self.game();
+                Ok(match game.outcome() {
+                    Outcome::InProgress => format!("in_progress {}", game.to_move().letter()),
+                    Outcome::Win(player, cells) => {
+                        let cells: Vec<String> = cells.iter().map(usize::to_string).collect();
+                        format!("win {} {}", player.letter(), cells.join(" "))
+                            .trim_end()
+                            .to_string()
+                    }
+                    Outcome::Draw => "draw".to_string(),
+                })
+            }
+            "setoption" => self.set_option(args),
+            _ => Err(format!("unknown command '{}'", command)),
+        }
+    }
+
+    fn set_option(&mut self, args: &[&str]) -> Result<String, String> {
+        let Some((&name, value)) = args.split_first().filter(|(_, value)| !value.is_empty()) else {
+            return Err("usage: setoption <name> <value>".to_string());
+        };
+        let value = value.join(" ");
+        match name {
+            "difficulty" => {
+                self.strategy = strategy::strategy_by_name(&value)
+                    .ok_or_else(|| format!("unknown strategy '{}'", value))?;
+            }
+            "variant" => {
+                self.variant = parse_variant(&value)?;
+                self.moves.clear();
+            }
+            "first" => {
+                self.first_player = Player::from_letter(&value.to_uppercase())
+                    .ok_or_else(|| format!("expected M or H, got '{}'", value))?;
+            }
+            _ => return Err(format!("unknown option '{}'", name)),
+        }
+        Ok(String::new())
+    }
+
+    /// The current position.
+    fn game(&self) -> Box<dyn Game> {
+        let first_player = self.moves.first().map_or(self.first_player, |mv| mv.player);
+        let mut game = self.variant.new_game(first_player);
+        for &mv in &self.moves {
+            game.play(mv);
+        }
+        game
+    }
+
+    /// Fails on the first move of `moves` the variant doesn't allow.
+    fn check_moves(&self, moves: &[Move]) -> Result<(), String> {
+        let first_player = moves.first().map_or(self.first_player, |mv| mv.player);
+        let mut game = self.variant.new_game(first_player);
+        for (ply, &mv) in moves.iter().enumerate() {
+            if let Some(reason) = game::illegal_reason(game.as_ref(), &mv) {
+                return Err(format!(
+                    "illegal move {} at ply {}: {}",
+                    mv,
+                    ply + 1,
+                    reason
+                ));
+            }
+            game.play(mv);
+        }
+        Ok(())
+    }
+}
+
+fn parse_move(text: &str) -> Result<Move, String> {
+    Move::from_notation(&text.to_uppercase()).ok_or_else(|| format!("invalid move '{}'", text))
+}
+
+fn parse_variant(name: &str) -> Result<Variant, String> {
+    Variant::from_name(name).ok_or_else(|| format!("unknown variant '{}'", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn success(text: &str) -> Option<Response> {
+        Some(Response::Success(text.to_string()))
+    }
+
+    fn failure(text: &str) -> Option<Response> {
+        Some(Response::Failure(text.to_string()))
+    }
+
+    #[test]
+    fn blank_lines_and_comments_get_no_reply() {
+        let mut session = Session::new();
+        for line in ["", "   ", "# a comment", "  # indented comment"] {
+            assert_eq!(session.execute(line), None, "{:?}", line);
+        }
+        assert_eq!(session.execute("  name  "), success("ttt-engine"));
+        assert_eq!(session.execute("fly"), failure("unknown command 'fly'"));
+        assert_eq!(
+            Response::Success(String::new()).to_string(),
+            "=\n\n".to_string()
+        );
+        assert_eq!(failure("x").unwrap().to_string(), "? x\n\n".to_string());
+    }
+
+    #[test]
+    fn position_sets_the_moves() {
+        let mut session = Session::new();
+        assert_eq!(
+            session.execute("position startpos moves M4 h0"),
+            success("")
+        );
+        assert_eq!(session.execute("status"), success("in_progress M"));
+        assert_eq!(session.execute("position"), success(""));
+        assert_eq!(session.execute("status"), success("in_progress H"));
+        assert_eq!(
+            session.execute("position M4 H0"),
+            failure("expected 'moves', got 'M4'")
+        );
+        assert_eq!(
+            session.execute("position moves M4 M0"),
+            failure("illegal move M0 at ply 2: it is H's turn")
+        );
+        assert_eq!(
+            session.execute("position moves M4 Z0"),
+            failure("invalid move 'Z0'")
+        );
+    }
+
+    #[test]
+    fn illegal_play_leaves_the_position_alone() {
+        let mut session = Session::new();
+        session.execute("position moves M4");
+        assert_eq!(
+            session.execute("play H4"),
+            failure("illegal move H4 at ply 2: the cell is taken")
+        );
+        assert_eq!(
+            session.execute("play H9"),
+            failure("illegal move H9 at ply 2: there is no cell 9")
+        );
+        assert_eq!(session.execute("play"), failure("usage: play <move>"));
+        assert_eq!(session.execute("status"), success("in_progress H"));
+        assert_eq!(session.execute("play H0"), success(""));
+        assert_eq!(session.execute("status"), success("in_progress M"));
+    }
+
+    #[test]
+    fn go_answers_without_playing() {
+        let mut session = Session::new();
+        session.execute("position moves M0 H3 M1 H4");
+        assert_eq!(session.execute("go"), success("M2"));
+        assert_eq!(session.execute("status"), success("in_progress M"));
+
+        // Only one cell is left
+        session.execute("setoption difficulty random");
+        session.execute("position moves M0 H1 M2 H4 M3 H5 M7 H6");
+        assert_eq!(session.execute("go"), success("M8"));
+
+        session.execute("play M8");
+        assert_eq!(session.execute("go"), failure("game over"));
+    }
+
+    #[test]
+    fn status_reports_wins_and_draws() {
+        let mut session = Session::new();
+        session.execute("position moves M0 H3 M1 H4 M2");
+        assert_eq!(session.execute("status"), success("win M 0 1 2"));
+        session.execute("position moves M0 H4 M8 H1 M7 H6 M2 H5 M3");
+        assert_eq!(session.execute("status"), success("draw"));
+    }
+
+    #[test]
+    fn setoption_changes_the_session() {
+        let mut session = Session::new();
+        assert_eq!(session.execute("setoption first m"), success(""));
+        assert_eq!(session.execute("status"), success("in_progress M"));
+        assert_eq!(
+            session.execute("setoption first X"),
+            failure("expected M or H, got 'X'")
+        );
+
+        assert_eq!(session.execute("setoption difficulty Hard"), success(""));
+        assert_eq!(
+            session.execute("setoption difficulty Expert"),
+            failure("unknown strategy 'Expert'")
+        );
+
+        session.execute("position moves M4");
+        assert_eq!(session.execute("setoption variant ultimate"), success(""));
+        // Switching the variant starts over
+        assert_eq!(session.execute("status"), success("in_progress M"));
+        assert_eq!(session.execute("play M80"), success(""));
+        assert_eq!(
+            session.execute("setoption variant chess"),
+            failure("unknown variant 'chess'")
+        );
+        assert_eq!(
+            session.execute("setoption variant"),
+            failure("usage: setoption <name> <value>")
+        );
+        assert_eq!(
+            session.execute("setoption colour red"),
+            failure("unknown option 'colour'")
+        );
+    }
+}
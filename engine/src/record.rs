@@ -0,0 +1,416 @@
+//! Game records: a plain-text format to archive games and load them back.
+//!
+//! ```text
+//! [Variant "3×3"]
+//! [Human "You"]
+//! [Machine "Perfect"]
+//! [Date "2026-10-18"]
+//! [Result "M"]
+//!
+//! Mb2 Ha1 Mc1 Ha3 Ma2 Hc2 Mb3 Hb1 Mc3
+//! ```
+//!
+//! Headers come first, one per line, with `"` and `\` in values escaped by a backslash.
+//! `Result` is `M` or `H` for the winner, `Draw`, or `*` for an unfinished game.
+//! Other headers are kept as tags. The moves follow,
+//! in index (`M4`) or algebraic (`Mb2`) notation as defined in `notation`, separated by
+//! whitespace or `->`, so the path keys of the win graph (`M0->H4->M1`) read as records too.
+
+use crate::game::{self, Game, Variant};
+use crate::notation::{Coordinates, Notation};
+use crate::rules::{Move, Outcome, Player};
+use std::error::Error;
+use std::fmt;
+
+/// Moves per line when writing a record.
+const MOVES_PER_LINE: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRecord {
+    pub variant: Variant,
+    /// Who played each side, e.g. "You", "Player 1" or a strategy name.
+    pub human: String,
+    pub machine: String,
+    pub date: String,
+    /// Any other headers, in order, e.g. settings an app stores with the game.
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<Move>,
+}
+
+/// A record that can't be read, with the line it went wrong on (counted from 1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// The `Result` header for `outcome`.
+fn result_text(outcome: &Outcome) -> &'static str {
+    match outcome {
+        Outcome::Win(player, _) => player.letter(),
+        Outcome::Draw => "Draw",
+        Outcome::InProgress => "*",
+    }
+}
+
+impl GameRecord {
+    pub fn new(variant: Variant, moves: Vec<Move>) -> Self {
+        GameRecord {
+            variant,
+            human: String::new(),
+            machine: String::new(),
+            date: String::new(),
+            tags: Vec::new(),
+            moves,
+        }
+    }
+
+    /// The final position; the first move decides who opened.
+    pub fn game(&self) -> Box<dyn Game> {
+        self.variant.replay(&self.moves)
+    }
+
+    /// The record as text, with the moves in `notation`.
+    pub fn write(&self, notation: Notation) -> String {
+        let game = self.game();
+        let coordinates = Coordinates::new(game.as_ref());
+        let mut text = String::new();
+        for (name, value) in [
+            ("Variant", self.variant.name()),
+            ("Human", self.human.clone()),
+            ("Machine", self.machine.clone()),
+            ("Date", self.date.clone()),
+            ("Result", result_text(&game.outcome()).to_string()),
+        ]
+        .into_iter()
+        .chain(
+            self.tags
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.clone())),
+        ) {
+            text.push_str(&format!("[{} \"{}\"]\n", name, escape(&value)));
+        }
+        text.push('\n');
+        for line in self.moves.chunks(MOVES_PER_LINE) {
+            let moves: Vec<String> = line
+                .iter()
+                .map(|mv| coordinates.format_move(mv, notation))
+                .collect();
+            text.push_str(&moves.join(" "));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Reads a record written by `write`, or by hand. Every move is checked against
+    /// the rules of the variant, and the `Result` header, if given, against the moves.
+    pub fn parse(text: &str) -> Result<GameRecord, ParseError> {
+        let mut record = GameRecord::new(Variant::default(), Vec::new());
+        let mut result: Option<(usize, String)> = None;
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim()));
+
+        // Headers, up to the first line that isn't one
+        let mut move_lines = Vec::new();
+        for (number, line) in lines.by_ref() {
+            if line.is_empty() {
+                continue;
+            }
+            if !line.starts_with('[') {
+                move_lines.push((number, line));
+                break;
+            }
+            let error = |message: String| ParseError {
+                line: number,
+                message,
+            };
+            let (name, value) = parse_header(line).ok_or_else(|| {
+                error(format!(
+                    "expected a header like [Name \"value\"], got {}",
+                    line
+                ))
+            })?;
+            match name {
+                "Variant" => {
+                    record.variant = Variant::from_name(&value)
+                        .ok_or_else(|| error(format!("unknown variant \"{}\"", value)))?;
+                }
+                "Human" => record.human = value,
+                "Machine" => record.machine = value,
+                "Date" => record.date = value,
+                "Result" => {
+                    if !["M", "H", "Draw", "*"].contains(&value.as_str()) {
+                        return Err(error(format!(
+                            "unknown result \"{}\", expected M, H, Draw or *",
+                            value
+                        )));
+                    }
+                    result = Some((number, value));
+                }
+                _ => record.tags.push((name.to_string(), value)),
+            }
+        }
+        move_lines.extend(lines);
+
+        // Moves, checked one by one as they are played
+        let mut game: Option<Box<dyn Game>> = None;
+        let coordinates = Coordinates::new(record.variant.new_game(Player::Human).as_ref());
+        for (number, line) in move_lines {
+            for word in line.split_whitespace().flat_map(|word| word.split("->")) {
+                if word.is_empty() {
+                    continue;
+                }
+                if word.starts_with('[') {
+                    return Err(ParseError {
+                        line: number,
+                        message: "headers must come before the moves".to_string(),
+                    });
+                }
+                let mv = coordinates.parse_move(word).ok_or_else(|| ParseError {
+                    line: number,
+                    message: format!("\"{}\" is not a move", word),
+                })?;
+                // The first move decides who opened
+                let game = game.get_or_insert_with(|| record.variant.new_game(mv.player));
+                if let Some(reason) = game::illegal_reason(game.as_ref(), &mv) {
+                    return Err(ParseError {
+                        line: number,
+                        message: format!(
+                            "move {} ({}) is illegal: {}",
+                            record.moves.len() + 1,
+                            word,
+                            reason
+                        ),
+                    });
+                }
+                game.play(mv);
+                record.moves.push(mv);
+            }
+        }
+
+        if let Some((number, expected)) = result {
+            let outcome = record.game().outcome();
+            if expected != result_text(&outcome) {
+                let actual = match outcome {
+                    Outcome::Win(player, _) => format!("a win for {}", player.letter()),
+                    Outcome::Draw => "a draw".to_string(),
+                    Outcome::InProgress => "an unfinished game".to_string(),
+                };
+                return Err(ParseError {
+                    line: number,
+                    message: format!(
+                        "the result is given as {}, but the moves make {}",
+                        expected, actual
+                    ),
+                });
+            }
+        }
+        Ok(record)
+    }
+}
+
+/// A header value with `"` and `\` escaped by a backslash.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Splits `[Name "value"]` into its name and value, undoing `escape`.
+fn parse_header(line: &str) -> Option<(&str, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?;
+    let (name, value) = inner.split_once(' ')?;
+    let quoted = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.push(chars.next()?),
+            // A quote must be escaped inside the value
+            '"' => return None,
+            c => value.push(c),
+        }
+    }
+    Some((name, value))
+}
+
+/// Today's date (UTC), like `2026-10-18`. Not on the web, where the system clock
+/// isn't available to Rust; use `date_from_unix` with the browser's time there.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn today() -> String {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    date_from_unix(seconds)
+}
+
+/// The calendar date (UTC) of a Unix timestamp, like `2026-10-18`.
+pub fn date_from_unix(seconds: u64) -> String {
+    // Days to civil date, after Howard Hinnant's `civil_from_days`
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Mark;
+
+    /// A game of `variant` played out by always taking a legal move picked by the ply,
+    /// so the games wander over the board and mix the marks.
+    fn played_out(variant: Variant) -> Vec<Move> {
+        let mut game = variant.new_game(Player::Machine);
+        let mut moves = Vec::new();
+        loop {
+            let legal = game.legal_moves();
+            if legal.is_empty() {
+                return moves;
+            }
+            let mv = legal[(moves.len() * 7 + 3) % legal.len()];
+            game.play(mv);
+            moves.push(mv);
+        }
+    }
+
+    fn round_trip(variant: Variant) {
+        let record = GameRecord {
+            human: "You".to_string(),
+            machine: "Perfect".to_string(),
+            date: "2026-10-18".to_string(),
+            tags: vec![("Mode".to_string(), "0".to_string())],
+            ..GameRecord::new(variant, played_out(variant))
+        };
+        assert!(record.moves.len() > 1);
+        for notation in [Notation::Index, Notation::Algebraic] {
+            let text = record.write(notation);
+            assert_eq!(GameRecord::parse(&text), Ok(record.clone()), "{}", text);
+        }
+    }
+
+    fn parse_error(text: &str) -> ParseError {
+        GameRecord::parse(text).expect_err(text)
+    }
+
+    #[test]
+    fn round_trips_in_both_notations() {
+        round_trip(Variant::default());
+        round_trip(Variant::Ultimate);
+        round_trip(Variant::Notakto { boards: 3 });
+        round_trip(Variant::OrderAndChaos);
+    }
+
+    #[test]
+    fn round_trips_chosen_marks() {
+        let moves = played_out(Variant::Wild);
+        assert!(moves.iter().all(|mv| mv.mark.is_some()));
+        assert!(moves.iter().any(|mv| mv.mark == Some(Mark::O)));
+        round_trip(Variant::Wild);
+    }
+
+    #[test]
+    fn round_trips_quotes_and_backslashes() {
+        let record = GameRecord {
+            human: "Ann \"the Ace\" O'Neil".to_string(),
+            machine: "C:\\bots\\".to_string(),
+            tags: vec![("Event".to_string(), "\"Club\" night \\o/".to_string())],
+            ..GameRecord::new(Variant::default(), vec![Move::new(4, Player::Human)])
+        };
+        let text = record.write(Notation::Algebraic);
+        assert!(
+            text.contains("[Human \"Ann \\\"the Ace\\\" O'Neil\"]"),
+            "{}",
+            text
+        );
+        assert_eq!(GameRecord::parse(&text), Ok(record));
+    }
+
+    #[test]
+    fn reads_hand_written_records() {
+        let record = GameRecord::parse("[Result \"M\"]\nM0->H4->M1 Hc1\n\nMa2 Hc3 Ma3").unwrap();
+        assert_eq!(record.variant, Variant::default());
+        assert_eq!(record.moves.len(), 7);
+        assert_eq!(record.moves[3], Move::new(2, Player::Human));
+
+        let record = GameRecord::parse("[Variant \"notakto on 3 boards\"]\nHd1 M10").unwrap();
+        assert_eq!(record.variant, Variant::Notakto { boards: 3 });
+        assert_eq!(record.moves[0], Move::new(9, Player::Human));
+        assert_eq!(record.moves[1], Move::new(10, Player::Machine));
+    }
+
+    #[test]
+    fn rejects_a_bad_header() {
+        let error = parse_error("[Variant \"3×3\"]\n[Human You]\n\nM4");
+        assert_eq!(error.line, 2);
+        assert_eq!(
+            error.message,
+            "expected a header like [Name \"value\"], got [Human You]"
+        );
+        let error = parse_error("[Human \"Ann \"the Ace\"\"]\n\nM4");
+        assert_eq!(error.line, 1);
+    }
+
+    #[test]
+    fn rejects_an_unknown_variant() {
+        let error = parse_error("[Variant \"Chess\"]\n\nM4");
+        assert_eq!(error.line, 1);
+        assert_eq!(error.message, "unknown variant \"Chess\"");
+    }
+
+    #[test]
+    fn rejects_a_header_after_the_moves() {
+        let error = parse_error("M4 H0\n[Result \"*\"]");
+        assert_eq!(error.line, 2);
+        assert_eq!(error.message, "headers must come before the moves");
+    }
+
+    #[test]
+    fn rejects_what_is_not_a_move() {
+        let error = parse_error("M4 Hz9");
+        assert_eq!(error.line, 1);
+        assert_eq!(error.message, "\"Hz9\" is not a move");
+    }
+
+    #[test]
+    fn rejects_an_illegal_move() {
+        let error = parse_error("[Variant \"3×3\"]\n\nM4 H0\nMb2");
+        assert_eq!(error.line, 4);
+        assert_eq!(error.message, "move 3 (Mb2) is illegal: the cell is taken");
+    }
+
+    #[test]
+    fn rejects_a_result_the_moves_dont_make() {
+        let error = parse_error("[Result \"H\"]\n\nM0 H3 M1 H4 M2");
+        assert_eq!(error.line, 1);
+        assert_eq!(
+            error.message,
+            "the result is given as H, but the moves make a win for M"
+        );
+        let error = parse_error("[Variant \"3×3\"]\n[Result \"Draw\"]\nM4");
+        assert_eq!(error.line, 2);
+        assert_eq!(
+            error.message,
+            "the result is given as Draw, but the moves make an unfinished game"
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Core tic-tac-toe rules shared by the AI (`minimax`, `win_graph`) and the front-ends.
+
+use std::fmt;
+
+/// One of the two sides of the game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Player {
+    Machine,
+    Human,
+}
+
+impl Player {
+    /// The player who moves after this one.
+    pub fn opponent(self) -> Player {
+        match self {
+            Player::Machine => Player::Human,
+            Player::Human => Player::Machine,
+        }
+    }
+
+    /// The letter used for this player in move records and path keys.
+    pub fn letter(self) -> &'static str {
+        match self {
+            Player::Machine => "M",
+            Player::Human => "H",
+        }
+    }
+
+    /// Parses a letter written by `letter`.
+    pub fn from_letter(letter: &str) -> Option<Player> {
+        match letter {
+            "M" => Some(Player::Machine),
+            "H" => Some(Player::Human),
+            _ => None,
+        }
+    }
+}
+
+/// The two symbols drawn on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mark {
+    X,
+    O,
+}
+
+impl Mark {
+    /// The other symbol.
+    pub fn other(self) -> Mark {
+        match self {
+            Mark::X => Mark::O,
+            Mark::O => Mark::X,
+        }
+    }
+
+    /// The letter used for this mark in move records and on the board.
+    pub fn letter(self) -> &'static str {
+        match self {
+            Mark::X => "X",
+            Mark::O => "O",
+        }
+    }
+
+    /// Parses a letter written by `letter`.
+    pub fn from_letter(letter: &str) -> Option<Mark> {
+        match letter {
+            "X" => Some(Mark::X),
+            "O" => Some(Mark::O),
+            _ => None,
+        }
+    }
+}
+
+/// A single move: which cell was taken, by which player, and with which mark in
+/// variants where the player picks it (`None` when the side's own symbol is placed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub idx: usize,
+    pub player: Player,
+    pub mark: Option<Mark>,
+}
+
+impl Move {
+    pub fn new(idx: usize, player: Player) -> Self {
+        Move {
+            idx,
+            player,
+            mark: None,
+        }
+    }
+
+    /// The same move, placing `mark`.
+    pub fn with_mark(self, mark: Mark) -> Self {
+        Move {
+            mark: Some(mark),
+            ..self
+        }
+    }
+
+    /// Parses the notation written by `Display`: the player letter, the cell index,
+    /// and the mark letter if the player picked one, like `M4`, `H10` or `H4O`.
+    pub fn from_notation(text: &str) -> Option<Move> {
+        let player = Player::from_letter(text.get(..1)?)?;
+        let rest = &text[1..];
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let idx = rest[..digits].parse().ok()?;
+        let mv = Move::new(idx, player);
+        match &rest[digits..] {
+            "" => Some(mv),
+            mark => Some(mv.with_mark(Mark::from_letter(mark)?)),
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.player.letter(), self.idx)?;
+        match self.mark {
+            Some(mark) => write!(f, "{}", mark.letter()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// State of a game: still running, won (with the cells to highlight), or drawn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    InProgress,
+    Win(Player, Vec<usize>),
+    Draw,
+}
+
+/// The eight winning lines of the 3×3 board, by cell index.
+pub const LINES: [[usize; 3]; 8] = [
+    [0, 1, 2],
+    [3, 4, 5],
+    [6, 7, 8],
+    [0, 3, 6],
+    [1, 4, 7],
+    [2, 5, 8],
+    [0, 4, 8],
+    [2, 4, 6],
+];
+
+/// The 8 symmetries of the square: `SYMMETRIES[s][cell]` is where `cell` ends up.
+/// Index 0 is the identity.
+const SYMMETRIES: [[usize; 9]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8], // identity
+    [2, 5, 8, 1, 4, 7, 0, 3, 6], // rotate 90° clockwise
+    [8, 7, 6, 5, 4, 3, 2, 1, 0], // rotate 180°
+    [6, 3, 0, 7, 4, 1, 8, 5, 2], // rotate 90° counter-clockwise
+    [2, 1, 0, 5, 4, 3, 8, 7, 6], // mirror left-right
+    [6, 7, 8, 3, 4, 5, 0, 1, 2], // mirror top-bottom
+    [0, 3, 6, 1, 4, 7, 2, 5, 8], // mirror on the main diagonal
+    [8, 5, 2, 7, 4, 1, 6, 3, 0], // mirror on the anti-diagonal
+];
+
+const FULL: u16 = 0b1_1111_1111;
+
+/// Bit mask of a winning line.
+const fn line_mask(line: [usize; 3]) -> u16 {
+    (1 << line[0]) | (1 << line[1]) | (1 << line[2])
+}
+
+const WIN_MASKS: [u16; 8] = [
+    line_mask(LINES[0]),
+    line_mask(LINES[1]),
+    line_mask(LINES[2]),
+    line_mask(LINES[3]),
+    line_mask(LINES[4]),
+    line_mask(LINES[5]),
+    line_mask(LINES[6]),
+    line_mask(LINES[7]),
+];
+
+/// A 3×3 position as one bitboard per player (bit `i` set = cell `i` taken).
+/// Cheap to copy and hash, and independent of the order the moves were played in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Board {
+    machine: u16,
+    human: u16,
+}
+
+impl Board {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Who holds `idx`, if anyone.
+    pub fn get(&self, idx: usize) -> Option<Player> {
+        let bit = 1 << idx;
+        if self.machine & bit != 0 {
+            Some(Player::Machine)
+        } else if self.human & bit != 0 {
+            Some(Player::Human)
+        } else {
+            None
+        }
+    }
+
+    /// Puts `player` on `idx` (or clears it with `None`).
+    pub fn set(&mut self, idx: usize, player: Option<Player>) {
+        let bit = 1 << idx;
+        self.machine &= !bit;
+        self.human &= !bit;
+        match player {
+            Some(Player::Machine) => self.machine |= bit,
+            Some(Player::Human) => self.human |= bit,
+            None => {}
+        }
+    }
+
+    /// A copy of this board with `player` on `idx`.
+    pub fn with(mut self, idx: usize, player: Player) -> Self {
+        self.set(idx, Some(player));
+        self
+    }
+
+    pub fn is_free(&self, idx: usize) -> bool {
+        (self.machine | self.human) & (1 << idx) == 0
+    }
+
+    /// The free cells, in index order.
+    pub fn empty_cells(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..9).filter(|&idx| self.is_free(idx))
+    }
+
+    pub fn empty_count(&self) -> usize {
+        (FULL & !(self.machine | self.human)).count_ones() as usize
+    }
+
+    pub fn is_full(&self) -> bool {
+        (self.machine | self.human) == FULL
+    }
+
+    /// Returns the player holding a complete line, and that line, if any.
+    pub fn winning_line(&self) -> Option<(Player, [usize; 3])> {
+        for (mask, line) in WIN_MASKS.iter().zip(LINES) {
+            if self.machine & mask == *mask {
+                return Some((Player::Machine, line));
+            }
+            if self.human & mask == *mask {
+                return Some((Player::Human, line));
+            }
+        }
+        None
+    }
+
+    /// Returns the player holding a complete line, if any.
+    pub fn winner(&self) -> Option<Player> {
+        self.winning_line().map(|(player, _)| player)
+    }
+
+    /// The cells the player to move may take; none once the game is over.
+    pub fn legal_moves(&self) -> Vec<usize> {
+        if self.winner().is_some() {
+            return Vec::new();
+        }
+        self.empty_cells().collect()
+    }
+
+    /// Applies symmetry `sym` (an index into `SYMMETRIES`).
+    fn transform(&self, sym: usize) -> Board {
+        let map = &SYMMETRIES[sym];
+        let mut out = Board::new();
+        for (cell, &to) in map.iter().enumerate() {
+            out.set(to, self.get(cell));
+        }
+        out
+    }
+
+    /// The smallest of the 8 symmetric variants of this board, together with the
+    /// symmetry that produces it. Use `from_canonical` to map cells back.
+    pub fn canonical(&self) -> (Board, usize) {
+        (0..SYMMETRIES.len())
+            .map(|sym| (self.transform(sym), sym))
+            .min()
+            .unwrap_or((*self, 0))
+    }
+
+    /// Maps `cell` of this board to the matching cell of the canonical board.
+    pub fn to_canonical(cell: usize, sym: usize) -> usize {
+        SYMMETRIES[sym][cell]
+    }
+
+    /// Maps `cell` of the canonical board back to this board.
+    pub fn from_canonical(cell: usize, sym: usize) -> usize {
+        SYMMETRIES[sym]
+            .iter()
+            .position(|&to| to == cell)
+            .unwrap_or(cell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn move_notation_round_trip() {
+        for mv in [
+            Move::new(4, Player::Machine),
+            Move::new(10, Player::Human),
+            Move::new(4, Player::Human).with_mark(Mark::O),
+            Move::new(35, Player::Machine).with_mark(Mark::X),
+        ] {
+            assert_eq!(Move::from_notation(&mv.to_string()), Some(mv));
+        }
+    }
+
+    #[test]
+    fn move_notation_rejects_garbage() {
+        for text in ["", "M", "X4", "4", "H4Q", "M-1"] {
+            assert_eq!(Move::from_notation(text), None, "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+//! Depth-limited alpha-beta over any `Game`, for boards too large to solve outright.
+//! Positions at the horizon are scored by the game's heuristic `evaluate`.
+
+use crate::game::Game;
+use crate::rules::{Move, Outcome};
+
+/// Score of a won position; any heuristic value stays far below it.
+pub const WIN_SCORE: i32 = 1_000_000_000;
+
+/// Score bound for the alpha-beta window; larger than any reachable score.
+const INFINITY: i32 = i32::MAX;
+
+/// Positions with at most this many empty cells are small enough to search to the end.
+const EXACT_SEARCH_CELLS: usize = 9;
+
+/// Rough cap on the positions one heuristic search may visit: every ply multiplies
+/// the work by the number of candidate moves, so wide positions are searched less deep.
+const SEARCH_BUDGET: usize = 100_000;
+
+/// Caps `depth` so that, with as many moves per ply as at the root, the search stays
+/// within `SEARCH_BUDGET` and takes well under a second.
+pub fn depth_within_budget(game: &dyn Game, depth: usize) -> usize {
+    let branching = game.candidate_moves().len().max(2);
+    let (mut allowed, mut nodes) = (1, branching);
+    while allowed < depth && nodes * branching <= SEARCH_BUDGET {
+        nodes *= branching;
+        allowed += 1;
+    }
+    allowed
+}
+
+/// The depth that searches `game` to the end, if few enough cells are left for that;
+/// scores from such a search are exact rather than heuristic.
+pub fn exact_depth(game: &dyn Game) -> Option<usize> {
+    let empty = (0..game.cell_count())
+        .filter(|&idx| game.cell(idx).is_none())
+        .count();
+    (empty <= EXACT_SEARCH_CELLS).then_some(empty)
+}
+
+/// Returns every move that scores best for the side to move when looking `depth` plies
+/// ahead (the root move included). Empty if the game is already over.
+pub fn best_moves(game: &dyn Game, depth: usize) -> Vec<Move> {
+    best_moves_and_score(game, depth).0
+}
+
+/// Like `best_moves`, together with their score, which is the value of the position for
+/// the side to move: at least `WIN_SCORE` if it can force a win within the horizon, at
+/// most `-WIN_SCORE` if it loses. Both come from one search; `None` if the game is over.
+pub fn best_moves_and_score(game: &dyn Game, depth: usize) -> (Vec<Move>, Option<i32>) {
+    root_scores(game, depth).into_iter().fold(
+        (Vec::new(), None),
+        |(mut best, best_score), (mv, score)| match best_score {
+            Some(best_score) if score < best_score => (best, Some(best_score)),
+            Some(best_score) if score == best_score => {
+                best.push(mv);
+                (best, Some(best_score))
+            }
+            _ => (vec![mv], Some(score)),
+        },
+    )
+}
+
+/// Score of every candidate move at the root. Each move gets a full window,
+/// so moves that tie with the best one are reported as ties.
+fn root_scores(game: &dyn Game, depth: usize) -> Vec<(Move, i32)> {
+    if game.outcome() != Outcome::InProgress {
+        return Vec::new();
+    }
+    game.candidate_moves()
+        .into_iter()
+        .map(|mv| {
+            let mut child = game.clone_box();
+            child.play(mv);
+            let score = -negamax(child.as_ref(), depth.max(1) - 1, -INFINITY, INFINITY);
+            (mv, score)
+        })
+        .collect()
+}
+
+fn negamax(game: &dyn Game, depth: usize, mut alpha: i32, beta: i32) -> i32 {
+    let turn = game.to_move();
+    match game.outcome() {
+        // Faster wins and slower losses score higher
+        Outcome::Win(winner, _) if winner == turn => return WIN_SCORE + depth as i32,
+        Outcome::Win(_, _) => return -(WIN_SCORE + depth as i32),
+        Outcome::Draw => return 0,
+        Outcome::InProgress => {}
+    }
+    if depth == 0 {
+        return game.evaluate(turn);
+    }
+
+    let mut best = -INFINITY;
+    for mv in game.candidate_moves() {
+        let mut child = game.clone_box();
+        child.play(mv);
+        let score = -negamax(child.as_ref(), depth - 1, -beta, -alpha);
+
+        best = best.max(score);
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+    best
+}
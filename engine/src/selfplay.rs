@@ -0,0 +1,65 @@
+//! Headless games between two strategies, for bulk evaluation without the UI.
+
+use crate::game::Variant;
+use crate::rules::{Move, Outcome, Player};
+use crate::strategy::Strategy;
+
+/// Tally of a match, from the point of view of the first strategy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchResult {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+/// Plays one game of `variant` to the end. `human_side` plays `Player::Human`,
+/// `machine_side` plays `Player::Machine`, and `first_player` opens.
+/// Returns the moves played and the final outcome.
+pub fn play_game(
+    variant: Variant,
+    human_side: &mut dyn Strategy,
+    machine_side: &mut dyn Strategy,
+    first_player: Player,
+) -> (Vec<Move>, Outcome) {
+    let mut game = variant.new_game(first_player);
+    let mut moves = Vec::new();
+
+    while game.outcome() == Outcome::InProgress {
+        let turn = game.to_move();
+        let choice = match turn {
+            Player::Human => human_side.choose_move(game.as_ref()),
+            Player::Machine => machine_side.choose_move(game.as_ref()),
+        };
+        let Some(mv) = choice else {
+            break;
+        };
+        game.play(mv);
+        moves.push(mv);
+    }
+
+    (moves, game.outcome())
+}
+
+/// Plays `games` games of `variant` between `first` (on the Human side) and `second`
+/// (on the Machine side), alternating who opens.
+pub fn play_match(
+    variant: Variant,
+    first: &mut dyn Strategy,
+    second: &mut dyn Strategy,
+    games: u32,
+) -> MatchResult {
+    let mut result = MatchResult::default();
+    for game in 0..games {
+        let opener = if game % 2 == 0 {
+            Player::Human
+        } else {
+            Player::Machine
+        };
+        match play_game(variant, first, second, opener).1 {
+            Outcome::Win(Player::Human, _) => result.wins += 1,
+            Outcome::Win(Player::Machine, _) => result.losses += 1,
+            Outcome::Draw | Outcome::InProgress => result.draws += 1,
+        }
+    }
+    result
+}
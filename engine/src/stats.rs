@@ -0,0 +1,295 @@
+//! Results of games against the engine, kept across sessions. Every finished game is
+//! one line of text: the date, the variant, the engine's strategy, who moved first and
+//! the result for the person, separated by tabs. The tallies are worked out from these
+//! lines when needed, so they can change without touching what is stored.
+
+use crate::rules::Player;
+use log::warn;
+
+/// How a game ended for the person playing against the engine.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GameResult {
+    Win,
+    Loss,
+    Draw,
+}
+
+impl GameResult {
+    pub fn name(self) -> &'static str {
+        match self {
+            GameResult::Win => "win",
+            GameResult::Loss => "loss",
+            GameResult::Draw => "draw",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [GameResult::Win, GameResult::Loss, GameResult::Draw]
+            .into_iter()
+            .find(|result| result.name() == name)
+    }
+}
+
+/// One finished game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameEntry {
+    pub date: String,
+    pub variant: String,
+    /// Name of the strategy the engine played.
+    pub difficulty: String,
+    pub first_player: Player,
+    pub result: GameResult,
+}
+
+impl GameEntry {
+    fn parse(line: &str) -> Option<Self> {
+        let [date, variant, difficulty, first_player, result] =
+            line.split('\t').collect::<Vec<_>>()[..]
+        else {
+            return None;
+        };
+        Some(GameEntry {
+            date: date.to_string(),
+            variant: variant.to_string(),
+            difficulty: difficulty.to_string(),
+            first_player: Player::from_letter(first_player)?,
+            result: GameResult::from_name(result)?,
+        })
+    }
+
+    fn write(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}",
+            self.date,
+            self.variant,
+            self.difficulty,
+            self.first_player.letter(),
+            self.result.name()
+        )
+    }
+
+    /// The games a tally is kept for: same variant, strategy and first mover.
+    pub fn key(&self) -> (&str, &str, Player) {
+        (&self.variant, &self.difficulty, self.first_player)
+    }
+}
+
+/// Results of a group of games, with the streak they end on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    /// The result of the last games and how many of them in a row ended that way.
+    pub streak: Option<(GameResult, u32)>,
+    /// The most wins in a row.
+    pub best_win_streak: u32,
+}
+
+impl Tally {
+    fn add(&mut self, result: GameResult) {
+        match result {
+            GameResult::Win => self.wins += 1,
+            GameResult::Loss => self.losses += 1,
+            GameResult::Draw => self.draws += 1,
+        }
+        self.streak = match self.streak {
+            Some((last, length)) if last == result => Some((result, length + 1)),
+            _ => Some((result, 1)),
+        };
+        if let Some((GameResult::Win, length)) = self.streak {
+            self.best_win_streak = self.best_win_streak.max(length);
+        }
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.losses + self.draws
+    }
+
+    /// Share of the games won, from 0 to 1; 0 before the first game.
+    pub fn win_rate(&self) -> f64 {
+        match self.games() {
+            0 => 0.0,
+            games => f64::from(self.wins) / f64::from(games),
+        }
+    }
+}
+
+/// The history of finished games, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Statistics {
+    pub games: Vec<GameEntry>,
+}
+
+impl Statistics {
+    /// Reads the history written by `write`. Lines that can't be read are skipped
+    /// (and logged), so one damaged line doesn't cost the rest of the history.
+    pub fn parse(text: &str) -> Self {
+        let games = text
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let entry = GameEntry::parse(line);
+                if entry.is_none() {
+                    warn!("Skipping unreadable statistics line: {}", line);
+                }
+                entry
+            })
+            .collect();
+        Statistics { games }
+    }
+
+    pub fn write(&self) -> String {
+        let mut text =
+            String::from("# date\tvariant\tdifficulty\tfirst mover\tresult for the person\n");
+        for entry in &self.games {
+            text.push_str(&entry.write());
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn record(&mut self, entry: GameEntry) {
+        self.games.push(entry);
+    }
+
+    /// All games together.
+    pub fn total(&self) -> Tally {
+        let mut tally = Tally::default();
+        for entry in &self.games {
+            tally.add(entry.result);
+        }
+        tally
+    }
+
+    /// A tally for every variant, strategy and first mover played, each with the latest
+    /// game of its group, the most recently played group first.
+    pub fn tallies(&self) -> Vec<(GameEntry, Tally)> {
+        let mut latest: Vec<&GameEntry> = Vec::new();
+        for entry in self.games.iter().rev() {
+            if !latest.iter().any(|other| other.key() == entry.key()) {
+                latest.push(entry);
+            }
+        }
+        latest
+            .into_iter()
+            .map(|last| {
+                let mut tally = Tally::default();
+                for entry in self.games.iter().filter(|entry| entry.key() == last.key()) {
+                    tally.add(entry.result);
+                }
+                (last.clone(), tally)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(difficulty: &str, first_player: Player, result: GameResult) -> GameEntry {
+        GameEntry {
+            date: "2026-10-18".to_string(),
+            variant: "3×3".to_string(),
+            difficulty: difficulty.to_string(),
+            first_player,
+            result,
+        }
+    }
+
+    #[test]
+    fn tally_follows_the_streaks() {
+        let mut tally = Tally::default();
+        assert_eq!(tally.win_rate(), 0.0);
+        for result in [
+            GameResult::Win,
+            GameResult::Win,
+            GameResult::Loss,
+            GameResult::Win,
+            GameResult::Win,
+            GameResult::Win,
+            GameResult::Draw,
+            GameResult::Draw,
+        ] {
+            tally.add(result);
+        }
+        assert_eq!((tally.wins, tally.losses, tally.draws), (5, 1, 2));
+        assert_eq!(tally.games(), 8);
+        assert_eq!(tally.streak, Some((GameResult::Draw, 2)));
+        assert_eq!(tally.best_win_streak, 3);
+        assert_eq!(tally.win_rate(), 5.0 / 8.0);
+    }
+
+    #[test]
+    fn write_and_parse_round_trip() {
+        let mut statistics = Statistics::default();
+        statistics.record(entry("Perfect", Player::Machine, GameResult::Draw));
+        statistics.record(entry("Easy", Player::Human, GameResult::Win));
+        statistics.record(entry("Easy", Player::Human, GameResult::Loss));
+        let text = statistics.write();
+        assert!(text.starts_with('#'));
+        assert_eq!(Statistics::parse(&text), statistics);
+        assert_eq!(Statistics::parse(""), Statistics::default());
+    }
+
+    #[test]
+    fn unreadable_lines_are_skipped() {
+        let text = "# header\n\
+                    2026-10-18\t3×3\tEasy\tH\twin\n\
+                    \n\
+                    not a game\n\
+                    2026-10-18\t3×3\tEasy\tX\twin\n\
+                    2026-10-18\t3×3\tEasy\tH\tforfeit\n\
+                    2026-10-18\t3×3\tEasy\tH\twin\textra\n\
+                    2026-10-19\t3×3\tHard\tM\tloss\n";
+        let statistics = Statistics::parse(text);
+        assert_eq!(
+            statistics.games,
+            vec![
+                entry("Easy", Player::Human, GameResult::Win),
+                GameEntry {
+                    date: "2026-10-19".to_string(),
+                    ..entry("Hard", Player::Machine, GameResult::Loss)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn tallies_group_games_latest_group_first() {
+        let mut statistics = Statistics::default();
+        for game in [
+            entry("Easy", Player::Human, GameResult::Win),
+            entry("Hard", Player::Human, GameResult::Loss),
+            entry("Easy", Player::Machine, GameResult::Draw),
+            entry("Easy", Player::Human, GameResult::Win),
+            entry("Hard", Player::Human, GameResult::Draw),
+        ] {
+            statistics.record(game);
+        }
+        let tallies: Vec<((String, Player), u32, u32, u32)> = statistics
+            .tallies()
+            .into_iter()
+            .map(|(last, tally)| {
+                (
+                    (last.difficulty, last.first_player),
+                    tally.wins,
+                    tally.losses,
+                    tally.draws,
+                )
+            })
+            .collect();
+        assert_eq!(
+            tallies,
+            vec![
+                (("Hard".to_string(), Player::Human), 0, 1, 1),
+                (("Easy".to_string(), Player::Human), 2, 0, 0),
+                (("Easy".to_string(), Player::Machine), 0, 0, 1),
+            ]
+        );
+        let total = statistics.total();
+        assert_eq!((total.wins, total.losses, total.draws), (2, 1, 2));
+        assert_eq!(total.streak, Some((GameResult::Draw, 1)));
+    }
+}
@@ -0,0 +1,234 @@
+//! Move-selection strategies: the difficulty levels of the engine and the classic
+//! win-path opponent. Anything implementing `Strategy` can play either side,
+//! against a person or against another strategy in self-play.
+//!
+//! Classic 3×3 positions are solved exactly; larger boards use the heuristic `search`.
+
+use crate::game::Game;
+use crate::minimax::Minimax;
+use crate::rules::{Move, Player};
+use crate::search;
+use crate::win_graph::WinGraph;
+use log::trace;
+use rand::Rng;
+use std::cell::RefCell;
+
+/// Picks moves for one side of the board.
+pub trait Strategy {
+    /// Name shown in the UI selectors and in self-play reports.
+    fn name(&self) -> &'static str;
+
+    /// Picks a move for the side to move in `game`, or `None` if the game is over.
+    fn choose_move(&mut self, game: &dyn Game) -> Option<Move>;
+}
+
+thread_local! {
+    /// One solver for the whole session, so its transposition table survives between moves.
+    static MINIMAX: RefCell<Minimax> = RefCell::new(Minimax::new());
+}
+
+/// Runs `f` with the shared solver.
+pub fn with_minimax<R>(f: impl FnOnce(&mut Minimax) -> R) -> R {
+    MINIMAX.with_borrow_mut(f)
+}
+
+/// Picks one of `choices` uniformly at random.
+fn pick_random<T: Copy>(choices: &[T]) -> Option<T> {
+    if choices.is_empty() {
+        return None;
+    }
+    Some(choices[rand::rng().random_range(0..choices.len())])
+}
+
+/// Moves that keep the Machine on a known win path; the `WinGraph` only knows
+/// Machine wins on the classic board, so this is empty for the Human side and other variants.
+fn win_path_moves(game: &dyn Game) -> Vec<Move> {
+    let turn = game.to_move();
+    match game.classic_board() {
+        Some(board) if turn == Player::Machine => WinGraph::get()
+            .next_moves(&board, turn)
+            .into_iter()
+            .map(|idx| Move::new(idx, turn))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// How strong the engine plays.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Difficulty {
+    /// Any empty tile, uniformly at random.
+    Random,
+    /// Plays the optimal move, but blunders into a random tile half of the time.
+    Easy,
+    /// Looks two plies ahead: takes immediate wins and blocks immediate threats.
+    Medium,
+    /// Looks four plies ahead, enough to see most forks coming.
+    Hard,
+    /// Solves the full game tree and never loses; on larger boards, searches as deep
+    /// as the board size allows, and to the end once few cells are left.
+    #[default]
+    Perfect,
+}
+
+/// Probability that `Difficulty::Easy` ignores the solver and plays a random tile.
+const EASY_BLUNDER_PROBABILITY: f64 = 0.5;
+
+impl Difficulty {
+    /// True if this move is played without looking at the position: always at Random,
+    /// and half of the time at Easy.
+    fn plays_at_random(self) -> bool {
+        match self {
+            Difficulty::Random => true,
+            Difficulty::Easy => rand::rng().random_bool(EASY_BLUNDER_PROBABILITY),
+            Difficulty::Medium | Difficulty::Hard | Difficulty::Perfect => false,
+        }
+    }
+
+    /// The moves the search rates best at this difficulty; the caller picks one of them.
+    fn candidate_moves(self, game: &dyn Game) -> Vec<Move> {
+        let Some(board) = game.classic_board() else {
+            return self.search_moves(game);
+        };
+        let turn = game.to_move();
+        let cells = with_minimax(|minimax| match self {
+            Difficulty::Medium => minimax.best_moves_to_depth(&board, turn, 2),
+            Difficulty::Hard => minimax.best_moves_to_depth(&board, turn, 4),
+            Difficulty::Random | Difficulty::Easy | Difficulty::Perfect => {
+                minimax.best_moves(&board, turn)
+            }
+        });
+        cells.into_iter().map(|idx| Move::new(idx, turn)).collect()
+    }
+
+    /// Like `candidate_moves`, with the heuristic search in place of the exact solver.
+    fn search_moves(self, game: &dyn Game) -> Vec<Move> {
+        let depth = match self {
+            Difficulty::Random | Difficulty::Easy | Difficulty::Medium => 2,
+            Difficulty::Hard => 3,
+            Difficulty::Perfect => match search::exact_depth(game) {
+                Some(depth) => return search::best_moves(game, depth),
+                None => 4,
+            },
+        };
+        search::best_moves(game, search::depth_within_budget(game, depth))
+    }
+}
+
+impl Strategy for Difficulty {
+    fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Random => "Random",
+            Difficulty::Easy => "Easy",
+            Difficulty::Medium => "Medium",
+            Difficulty::Hard => "Hard",
+            Difficulty::Perfect => "Perfect",
+        }
+    }
+
+    /// Random moves are picked uniformly from every legal move. Otherwise prefers the
+    /// searched candidates that stay on a Machine win path of the cached `WinGraph`,
+    /// then picks at random, so equally good moves still vary.
+    fn choose_move(&mut self, game: &dyn Game) -> Option<Move> {
+        if self.plays_at_random() {
+            return pick_random(&game.legal_moves());
+        }
+        let candidates = self.candidate_moves(game);
+        let on_path = win_path_moves(game);
+        let preferred: Vec<Move> = candidates
+            .iter()
+            .copied()
+            .filter(|mv| on_path.contains(mv))
+            .collect();
+        trace!(
+            "{} candidates for {:?}: {:?}, on a win path: {:?}",
+            self.name(),
+            game.to_move(),
+            candidates,
+            preferred
+        );
+
+        if preferred.is_empty() {
+            pick_random(&candidates)
+        } else {
+            pick_random(&preferred)
+        }
+    }
+}
+
+/// The original opponent: follows a Machine win path of the `WinGraph` when one
+/// continues from the current position, otherwise plays a random tile
+/// (always, outside the classic board).
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Classic;
+
+impl Strategy for Classic {
+    fn name(&self) -> &'static str {
+        "Classic"
+    }
+
+    fn choose_move(&mut self, game: &dyn Game) -> Option<Move> {
+        pick_random(&win_path_moves(game)).or_else(|| pick_random(&game.legal_moves()))
+    }
+}
+
+/// Every strategy, in the order of the strategy selectors of the window.
+pub fn all_strategies() -> Vec<Box<dyn Strategy>> {
+    vec![
+        Box::new(Difficulty::Random),
+        Box::new(Difficulty::Easy),
+        Box::new(Difficulty::Medium),
+        Box::new(Difficulty::Hard),
+        Box::new(Difficulty::Perfect),
+        Box::new(Classic),
+    ]
+}
+
+/// Maps a strategy selector index; unknown indices fall back to the default difficulty.
+pub fn strategy_by_index(index: i32) -> Box<dyn Strategy> {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| all_strategies().into_iter().nth(index))
+        .unwrap_or_else(|| Box::new(Difficulty::default()))
+}
+
+/// Looks a strategy up by its (case-insensitive) name.
+pub fn strategy_by_name(name: &str) -> Option<Box<dyn Strategy>> {
+    all_strategies()
+        .into_iter()
+        .find(|strategy| strategy.name().eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Variant;
+    use std::collections::HashSet;
+
+    #[test]
+    fn random_plays_every_empty_cell() {
+        let game = Variant::default()
+            .replay(&[Move::new(0, Player::Machine), Move::new(4, Player::Human)]);
+        let mut played = HashSet::new();
+        for _ in 0..400 {
+            let mv = Difficulty::Random.choose_move(game.as_ref()).unwrap();
+            assert!(game.legal_moves().contains(&mv));
+            played.insert(mv.idx);
+        }
+        assert_eq!(played, HashSet::from([1, 2, 3, 5, 6, 7, 8]));
+    }
+
+    #[test]
+    fn perfect_takes_a_win() {
+        let game = Variant::default().replay(&[
+            Move::new(0, Player::Machine),
+            Move::new(3, Player::Human),
+            Move::new(1, Player::Machine),
+            Move::new(4, Player::Human),
+        ]);
+        for _ in 0..20 {
+            let mv = Difficulty::Perfect.choose_move(game.as_ref());
+            assert_eq!(mv, Some(Move::new(2, Player::Machine)));
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Ultimate tic-tac-toe: nine classic boards in a 3×3 arrangement.
+//! The cell a player takes decides which sub-board the opponent must play in next;
+//! winning a sub-board claims it, and three claimed sub-boards in a line win the game.
+//!
+//! Cell `idx` is cell `idx % 9` of sub-board `idx / 9`, both numbered like the classic board.
+
+use crate::game::Game;
+use crate::rules::{Board, Move, Outcome, Player, LINES};
+
+/// Gap between the sub-boards, in tile units.
+const SUB_BOARD_GAP: f32 = 0.3;
+
+/// Value of a claimed sub-board; the center one takes part in more lines.
+const CLAIMED_VALUE: i32 = 100;
+const CLAIMED_CENTER_BONUS: i32 = 50;
+/// Value of two claimed sub-boards in a line the opponent hasn't blocked.
+const OPEN_PAIR_VALUE: i32 = 200;
+/// Value of two stones in a line of an undecided sub-board.
+const SMALL_PAIR_VALUE: i32 = 10;
+
+#[derive(Clone)]
+pub struct UltimateGame {
+    boards: [Board; 9],
+    /// Who claimed each sub-board, if anyone.
+    claimed: [Option<Player>; 9],
+    /// The sub-board the side to move must play in; `None` for any undecided one.
+    forced: Option<usize>,
+    to_move: Player,
+}
+
+impl UltimateGame {
+    pub fn new(first_player: Player) -> Self {
+        UltimateGame {
+            boards: [Board::new(); 9],
+            claimed: [None; 9],
+            forced: None,
+            to_move: first_player,
+        }
+    }
+
+    /// True once a sub-board is claimed or full; no more moves go there.
+    fn is_decided(&self, board: usize) -> bool {
+        self.claimed[board].is_some() || self.boards[board].is_full()
+    }
+
+    /// The sub-boards claimed so far, as a classic board.
+    fn claimed_board(&self) -> Board {
+        let mut board = Board::new();
+        for (idx, owner) in self.claimed.iter().enumerate() {
+            board.set(idx, *owner);
+        }
+        board
+    }
+
+    /// The sub-boards the side to move may play in.
+    fn playable_boards(&self) -> Vec<usize> {
+        if self.claimed_board().winner().is_some() {
+            return Vec::new();
+        }
+        match self.forced {
+            Some(board) => vec![board],
+            None => (0..9).filter(|&board| !self.is_decided(board)).collect(),
+        }
+    }
+}
+
+impl Game for UltimateGame {
+    fn cell_count(&self) -> usize {
+        81
+    }
+
+    fn cell_position(&self, idx: usize) -> (f32, f32) {
+        let (board, cell) = (idx / 9, idx % 9);
+        let offset = 3.0 + SUB_BOARD_GAP;
+        (
+            (board % 3) as f32 * offset + (cell % 3) as f32,
+            (board / 3) as f32 * offset + (cell / 3) as f32,
+        )
+    }
+
+    fn grid_size(&self) -> (f32, f32) {
+        let size = 9.0 + 2.0 * SUB_BOARD_GAP;
+        (size, size)
+    }
+
+    fn cell(&self, idx: usize) -> Option<Player> {
+        self.boards
+            .get(idx / 9)
+            .and_then(|board| board.get(idx % 9))
+    }
+
+    fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        self.playable_boards()
+            .into_iter()
+            .flat_map(|board| {
+                self.boards[board]
+                    .empty_cells()
+                    .map(move |cell| board * 9 + cell)
+            })
+            .map(|idx| Move::new(idx, self.to_move))
+            .collect()
+    }
+
+    /// The center cell of the center sub-board.
+    fn opening_moves(&self) -> Vec<Move> {
+        vec![Move::new(40, self.to_move)]
+    }
+
+    fn play(&mut self, mv: Move) {
+        let (board, cell) = (mv.idx / 9, mv.idx % 9);
+        if !self.playable_boards().contains(&board) || !self.boards[board].is_free(cell) {
+            return;
+        }
+        let player = self.to_move;
+        self.boards[board].set(cell, Some(player));
+        if self.claimed[board].is_none() {
+            self.claimed[board] = self.boards[board].winner();
+        }
+        self.forced = (!self.is_decided(cell)).then_some(cell);
+        self.to_move = player.opponent();
+    }
+
+    /// A win highlights the winning line of each of the three claimed sub-boards.
+    fn outcome(&self) -> Outcome {
+        if let Some((player, boards)) = self.claimed_board().winning_line() {
+            let cells = boards
+                .iter()
+                .filter_map(|&board| {
+                    let (_, line) = self.boards[board].winning_line()?;
+                    Some(line.map(|cell| board * 9 + cell))
+                })
+                .flatten()
+                .collect();
+            return Outcome::Win(player, cells);
+        }
+        if (0..9).all(|board| self.is_decided(board)) {
+            Outcome::Draw
+        } else {
+            Outcome::InProgress
+        }
+    }
+
+    /// Claimed sub-boards, pairs of them in open lines, and pairs of stones inside the
+    /// sub-boards still in play.
+    fn evaluate(&self, player: Player) -> i32 {
+        let sign = |owner: Player| if owner == player { 1 } else { -1 };
+        let mut score = 0;
+
+        for (board, owner) in self.claimed.iter().enumerate() {
+            if let Some(owner) = owner {
+                let bonus = if board == 4 { CLAIMED_CENTER_BONUS } else { 0 };
+                score += sign(*owner) * (CLAIMED_VALUE + bonus);
+            }
+        }
+
+        for line in LINES {
+            let owners: Vec<Option<Player>> = line.iter().map(|&b| self.claimed[b]).collect();
+            for side in [Player::Machine, Player::Human] {
+                let claimed = owners.iter().filter(|&&o| o == Some(side)).count();
+                let blocked = line.iter().any(|&b| {
+                    self.claimed[b] == Some(side.opponent())
+                        || (self.claimed[b].is_none() && self.boards[b].is_full())
+                });
+                if claimed == 2 && !blocked {
+                    score += sign(side) * OPEN_PAIR_VALUE;
+                }
+            }
+        }
+
+        for (board, cells) in self.boards.iter().enumerate() {
+            if self.is_decided(board) {
+                continue;
+            }
+            for line in LINES {
+                let stones: Vec<Option<Player>> = line.iter().map(|&c| cells.get(c)).collect();
+                for side in [Player::Machine, Player::Human] {
+                    let mine = stones.iter().filter(|&&s| s == Some(side)).count();
+                    let theirs = stones
+                        .iter()
+                        .filter(|&&s| s == Some(side.opponent()))
+                        .count();
+                    if mine == 2 && theirs == 0 {
+                        score += sign(side) * SMALL_PAIR_VALUE;
+                    }
+                }
+            }
+        }
+        score
+    }
+
+    /// The empty cells of the sub-board the side to move is sent to; nothing when it may
+    /// pick any sub-board.
+    fn active_cells(&self) -> Vec<usize> {
+        if self.forced.is_some() {
+            self.legal_moves().iter().map(|mv| mv.idx).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// The winning line of every claimed sub-board.
+    fn decided_lines(&self) -> Vec<(Player, Vec<usize>)> {
+        (0..9)
+            .filter_map(|board| {
+                let (player, line) = self.boards[board].winning_line()?;
+                Some((player, line.iter().map(|cell| board * 9 + cell).collect()))
+            })
+            .collect()
+    }
+
+    fn clone_box(&self) -> Box<dyn Game> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_move_forces_the_matching_sub_board() {
+        let mut game = UltimateGame::new(Player::Human);
+        assert_eq!(game.legal_moves().len(), 81);
+
+        // The top-left cell of the center sub-board sends the Machine to the top-left one
+        game.play(Move::new(36, Player::Human));
+        let forced: Vec<usize> = game.legal_moves().iter().map(|mv| mv.idx).collect();
+        assert_eq!(forced, (0..9).collect::<Vec<_>>());
+        assert_eq!(game.active_cells(), forced);
+
+        // Moves outside the forced sub-board are ignored
+        game.play(Move::new(40, Player::Machine));
+        assert_eq!(game.cell(40), None);
+        assert_eq!(game.to_move(), Player::Machine);
+
+        // The center cell sends the Human back to the center sub-board, minus its taken cell
+        game.play(Move::new(4, Player::Machine));
+        let forced: Vec<usize> = game.legal_moves().iter().map(|mv| mv.idx).collect();
+        assert_eq!(forced, (37..45).collect::<Vec<_>>());
+    }
+}
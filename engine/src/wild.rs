@@ -0,0 +1,287 @@
+//! Games where every move places X or O, whichever side makes it.
+//!
+//! Wild tic-tac-toe: on the classic board, whoever completes a line of three identical
+//! marks wins. Order and Chaos: on a 6×6 board, the opener (Order) wins as soon as five
+//! identical marks line up, whoever placed them; the other side (Chaos) wins if the board
+//! fills up without that.
+
+use crate::game::{Game, Marks};
+use crate::mnk::{line_value, Lines};
+use crate::rules::{Mark, Move, Outcome, Player};
+use std::rc::Rc;
+
+/// Who a completed line counts for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rules {
+    /// The side completing it.
+    Wild,
+    /// Always Order, the side that opened.
+    OrderAndChaos { order: Player },
+}
+
+#[derive(Clone)]
+pub struct WildGame {
+    width: usize,
+    k: usize,
+    rules: Rules,
+    lines: Rc<Lines>,
+    marks: Vec<Option<Mark>>,
+    /// Who placed each mark, for the UI and the move record.
+    placed_by: Vec<Option<Player>>,
+    /// Marks of each kind on every line: `[x, o]`.
+    counts: Vec<[u8; 2]>,
+    empty: usize,
+    to_move: Player,
+    /// The side that won by a completed line, and that line's index.
+    winner: Option<(Player, usize)>,
+}
+
+fn kind(mark: Mark) -> usize {
+    match mark {
+        Mark::X => 0,
+        Mark::O => 1,
+    }
+}
+
+impl WildGame {
+    fn new(size: usize, k: usize, rules: Rules, first_player: Player) -> Self {
+        let lines = Lines::new(size, size, 1, k);
+        WildGame {
+            width: size,
+            k,
+            rules,
+            counts: vec![[0, 0]; lines.lines.len()],
+            lines: Rc::new(lines),
+            marks: vec![None; size * size],
+            placed_by: vec![None; size * size],
+            empty: size * size,
+            to_move: first_player,
+            winner: None,
+        }
+    }
+
+    pub fn wild(first_player: Player) -> Self {
+        Self::new(3, 3, Rules::Wild, first_player)
+    }
+
+    /// Order and Chaos; `first_player` plays Order.
+    pub fn order_and_chaos(first_player: Player) -> Self {
+        Self::new(
+            6,
+            5,
+            Rules::OrderAndChaos {
+                order: first_player,
+            },
+            first_player,
+        )
+    }
+
+    /// Lines still open for one kind of mark, by how many of it they hold.
+    fn open_lines(&self) -> impl Iterator<Item = u8> + '_ {
+        self.counts.iter().filter_map(|count| match *count {
+            [x, 0] => Some(x),
+            [0, o] => Some(o),
+            _ => None,
+        })
+    }
+
+    /// How promising `mv` looks for the side to move; used to try strong moves first.
+    fn potential(&self, mv: &Move) -> i32 {
+        let Some(mark) = mv.mark else {
+            return 0;
+        };
+        let (same, other) = (kind(mark), 1 - kind(mark));
+        self.lines.through[mv.idx]
+            .iter()
+            .map(|&line| {
+                let count = self.counts[line];
+                match self.rules {
+                    // Completing a line wins, leaving one to complete loses
+                    Rules::Wild if count[other] == 0 && count[same] as usize + 1 == self.k => 1000,
+                    Rules::Wild if count[other] == 0 && count[same] as usize + 2 == self.k => -100,
+                    Rules::Wild => 0,
+                    // Order extends lines of one kind, Chaos spoils them with the other kind
+                    Rules::OrderAndChaos { order } if order == self.to_move => {
+                        if count[other] == 0 {
+                            line_value(self.k, count[same] + 1)
+                        } else {
+                            0
+                        }
+                    }
+                    Rules::OrderAndChaos { .. } => {
+                        if count[same] == 0 {
+                            line_value(self.k, count[other])
+                        } else {
+                            0
+                        }
+                    }
+                }
+            })
+            .sum()
+    }
+}
+
+impl Game for WildGame {
+    fn cell_count(&self) -> usize {
+        self.marks.len()
+    }
+
+    fn cell_position(&self, idx: usize) -> (f32, f32) {
+        ((idx % self.width) as f32, (idx / self.width) as f32)
+    }
+
+    fn grid_size(&self) -> (f32, f32) {
+        (self.width as f32, self.width as f32)
+    }
+
+    fn cell(&self, idx: usize) -> Option<Player> {
+        self.placed_by.get(idx).copied().flatten()
+    }
+
+    fn marks(&self) -> Marks {
+        Marks::Chosen
+    }
+
+    fn mark(&self, idx: usize) -> Option<Mark> {
+        self.marks.get(idx).copied().flatten()
+    }
+
+    fn to_move(&self) -> Player {
+        self.to_move
+    }
+
+    fn legal_moves(&self) -> Vec<Move> {
+        if self.winner.is_some() {
+            return Vec::new();
+        }
+        (0..self.marks.len())
+            .filter(|&idx| self.marks[idx].is_none())
+            .flat_map(|idx| {
+                [Mark::X, Mark::O].map(|mark| Move::new(idx, self.to_move).with_mark(mark))
+            })
+            .collect()
+    }
+
+    fn candidate_moves(&self) -> Vec<Move> {
+        let mut moves = self.legal_moves();
+        moves.sort_by_cached_key(|mv| -self.potential(mv));
+        moves
+    }
+
+    /// Either mark on the center cell(s).
+    fn opening_moves(&self) -> Vec<Move> {
+        let mut middle = vec![(self.width - 1) / 2, self.width / 2];
+        middle.dedup();
+        let mut moves = Vec::new();
+        for &y in &middle {
+            for &x in &middle {
+                for mark in [Mark::X, Mark::O] {
+                    moves.push(Move::new(y * self.width + x, self.to_move).with_mark(mark));
+                }
+            }
+        }
+        moves
+    }
+
+    fn play(&mut self, mv: Move) {
+        let Some(mark) = mv.mark else {
+            return;
+        };
+        let idx = mv.idx;
+        if self.winner.is_some() || self.marks.get(idx) != Some(&None) {
+            return;
+        }
+        let player = self.to_move;
+        self.marks[idx] = Some(mark);
+        self.placed_by[idx] = Some(player);
+        self.empty -= 1;
+        for &line in &self.lines.through[idx] {
+            let count = &mut self.counts[line];
+            count[kind(mark)] += 1;
+            if count[kind(mark)] as usize == self.k && self.winner.is_none() {
+                let winner = match self.rules {
+                    Rules::Wild => player,
+                    Rules::OrderAndChaos { order } => order,
+                };
+                self.winner = Some((winner, line));
+            }
+        }
+        self.to_move = player.opponent();
+    }
+
+    fn outcome(&self) -> Outcome {
+        match (self.winner, self.rules) {
+            (Some((player, line)), _) => Outcome::Win(player, self.lines.lines[line].clone()),
+            (None, _) if self.empty > 0 => Outcome::InProgress,
+            (None, Rules::Wild) => Outcome::Draw,
+            (None, Rules::OrderAndChaos { order }) => Outcome::Win(order.opponent(), Vec::new()),
+        }
+    }
+
+    /// Wild games are short enough to be searched to the end, so they are judged as even.
+    /// In Order and Chaos, every line still open for one kind of mark counts for Order.
+    fn evaluate(&self, player: Player) -> i32 {
+        match self.rules {
+            Rules::Wild => 0,
+            Rules::OrderAndChaos { order } => {
+                let score: i32 = self.open_lines().map(|n| line_value(self.k, n)).sum();
+                if player == order {
+                    score
+                } else {
+                    -score
+                }
+            }
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn Game> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wild_line_wins_for_the_side_completing_it() {
+        let mut game = WildGame::wild(Player::Machine);
+        for idx in [0, 1, 2] {
+            game.play(Move::new(idx, game.to_move()).with_mark(Mark::O));
+        }
+        assert_eq!(game.outcome(), Outcome::Win(Player::Machine, vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn order_wins_with_five_alike_whoever_placed_them() {
+        let mut game = WildGame::order_and_chaos(Player::Human);
+        game.play(Move::new(35, Player::Human).with_mark(Mark::O));
+        // Chaos and Order take turns placing X along the top row; Chaos places the fifth
+        for idx in 0..5 {
+            assert_eq!(game.outcome(), Outcome::InProgress);
+            game.play(Move::new(idx, game.to_move()).with_mark(Mark::X));
+        }
+        assert_eq!(
+            game.outcome(),
+            Outcome::Win(Player::Human, vec![0, 1, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn full_board_without_five_alike_wins_for_chaos() {
+        let mut game = WildGame::order_and_chaos(Player::Human);
+        for idx in 0..36 {
+            assert_eq!(game.outcome(), Outcome::InProgress);
+            // Runs of at most two alike in every row, column and diagonal
+            let (x, y) = (idx % 6, idx / 6);
+            let mark = if (x + 2 * y) % 4 < 2 {
+                Mark::X
+            } else {
+                Mark::O
+            };
+            game.play(Move::new(idx, game.to_move()).with_mark(mark));
+        }
+        assert_eq!(game.outcome(), Outcome::Win(Player::Machine, Vec::new()));
+        assert!(game.legal_moves().is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+use crate::rules::{Board, Move, Player};
+use crate::strategy::with_minimax;
+use log::trace;
+use petgraph::dot::{Config, Dot};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::Bfs;
+use petgraph::Graph;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Limit the maximum depth of sequences to keep memory small.
+/// If the Machine hasn't won by this depth, we stop and do not store deeper sequences.
+/// When the Human opens, the Machine is one ply behind, so it gets one extra ply.
+const MAX_DEPTH: usize = 5;
+
+/// Recursively collect all sequences up to `max_depth` where Machine eventually wins.
+/// We store *short* paths and do not explore further after the Machine wins or we hit the depth limit.
+fn collect_machine_wins_limited(
+    board: &mut Board,
+    path: &mut Vec<Move>,
+    turn: Player,
+    max_depth: usize,
+    results: &mut Vec<Vec<Move>>,
+) {
+    // If someone already won, store if it's Machine
+    if let Some(w) = board.winner() {
+        if w == Player::Machine {
+            results.push(path.clone());
+        }
+        return;
+    }
+
+    // If we've hit the depth limit, stop
+    // (the Machine hasn't won yet, so we do not store path)
+    if path.len() >= max_depth {
+        return;
+    }
+
+    // If board is full (draw), stop
+    if board.is_full() {
+        return;
+    }
+
+    // Otherwise, try all free cells
+    for idx in 0..9 {
+        if board.is_free(idx) {
+            // Make a move
+            board.set(idx, Some(turn));
+            path.push(Move::new(idx, turn));
+
+            // Next turn
+            collect_machine_wins_limited(board, path, turn.opponent(), max_depth, results);
+
+            // Undo
+            path.pop();
+            board.set(idx, None);
+        }
+    }
+}
+
+/// Build a minimal set of (path -> machine wins) up to MAX_DEPTH, for either player opening.
+/// The keys can't collide: they start with "M" or "H" depending on who opened.
+fn generate_limited_machine_wins() -> Vec<Vec<Move>> {
+    let mut board = Board::new();
+    let mut path = Vec::new();
+    let mut results = Vec::new();
+
+    collect_machine_wins_limited(
+        &mut board,
+        &mut path,
+        Player::Machine,
+        MAX_DEPTH,
+        &mut results,
+    );
+    collect_machine_wins_limited(
+        &mut board,
+        &mut path,
+        Player::Human,
+        MAX_DEPTH + 1,
+        &mut results,
+    );
+    results
+}
+
+/// Generate a string key for a path, e.g. "M0->H4->M1->..."
+fn sequence_key(seq: &[Move]) -> String {
+    if seq.is_empty() {
+        return "empty".to_string();
+    }
+    let parts: Vec<String> = seq.iter().map(Move::to_string).collect();
+    parts.join("->")
+}
+
+/// A position in the graph: the canonical board plus the player whose turn it is.
+/// Both openers share the empty board, so the side to move keeps them apart.
+/// Symmetric positions share one key, so the graph stores each of them once.
+type StateKey = (Board, Player);
+
+/// A position in the graph, in its canonical orientation.
+struct Node {
+    /// The first path key that reached the position, e.g. "M0->H4", turned like the board.
+    key: String,
+    board: Board,
+    turn: Player,
+}
+
+/// The board as a mini-board of three rows, `M` and `H` for the marks.
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in 0..3 {
+            for col in 0..3 {
+                let cell = self.board.get(row * 3 + col).map_or(".", Player::letter);
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Node {
+    /// Fill color by how the game ends from here with best play: red shades for the
+    /// Machine, green for the Human, yellow for a draw; the darker shades are games
+    /// already decided on the board.
+    fn color(&self) -> &'static str {
+        match self.board.winner() {
+            Some(Player::Machine) => "#e06666",
+            Some(Player::Human) => "#93c47d",
+            None => {
+                let value = with_minimax(|minimax| minimax.evaluate(&self.board, self.turn));
+                match (value.signum(), self.turn) {
+                    (0, _) => "#fff2cc",
+                    (1, Player::Machine) | (-1, Player::Human) => "#f4cccc",
+                    _ => "#d9ead3",
+                }
+            }
+        }
+    }
+}
+
+/// The win graph, built once on first use.
+static WIN_GRAPH: OnceLock<WinGraph> = OnceLock::new();
+
+/// Our main struct: every position on a Machine win path (of up to MAX_DEPTH moves),
+/// linked to the positions one move further along those paths.
+pub struct WinGraph {
+    /// Edges carry the cell played, in the canonical orientation of the source position.
+    graph: Graph<Node, usize>,
+    /// Canonical position -> node, so transpositions and symmetric positions share one node.
+    index: HashMap<StateKey, NodeIndex>,
+}
+
+impl WinGraph {
+    /// Returns the shared graph, building it on the first call.
+    pub fn get() -> &'static WinGraph {
+        WIN_GRAPH.get_or_init(Self::build)
+    }
+
+    /// Build the position graph from every limited Machine win path.
+    /// Each path contributes one node per prefix, so lookups work at any depth.
+    fn build() -> WinGraph {
+        let mut graph = Graph::<Node, usize>::new();
+        let mut index: HashMap<StateKey, NodeIndex> = HashMap::new();
+
+        for seq in generate_limited_machine_wins() {
+            let Some(first) = seq.first() else {
+                continue;
+            };
+
+            let mut board = Board::new();
+            let mut from = *index.entry((board, first.player)).or_insert_with(|| {
+                graph.add_node(Node {
+                    key: sequence_key(&[]),
+                    board,
+                    turn: first.player,
+                })
+            });
+
+            for (len, mv) in seq.iter().enumerate() {
+                let (_, from_sym) = board.canonical();
+                let cell = Board::to_canonical(mv.idx, from_sym);
+
+                board.set(mv.idx, Some(mv.player));
+                let (canonical, to_sym) = board.canonical();
+                let to = *index
+                    .entry((canonical, mv.player.opponent()))
+                    .or_insert_with(|| {
+                        // The path turned the same way as the board, so it ends on the board drawn
+                        let path: Vec<Move> = seq[..=len]
+                            .iter()
+                            .map(|&mv| Move {
+                                idx: Board::to_canonical(mv.idx, to_sym),
+                                ..mv
+                            })
+                            .collect();
+                        graph.add_node(Node {
+                            key: sequence_key(&path),
+                            board: canonical,
+                            turn: mv.player.opponent(),
+                        })
+                    });
+
+                // Symmetric moves can reach the same child, so compare the cell as well
+                if !graph
+                    .edges_connecting(from, to)
+                    .any(|edge| *edge.weight() == cell)
+                {
+                    graph.add_edge(from, to, cell);
+                }
+                from = to;
+            }
+        }
+
+        trace!(
+            "Win graph built: {} positions, {} moves",
+            graph.node_count(),
+            graph.edge_count()
+        );
+        WinGraph { graph, index }
+    }
+
+    /// Cells `turn` can play on `board` that keep the game on a Machine win path.
+    /// Empty if the position isn't on any such path.
+    pub fn next_moves(&self, board: &Board, turn: Player) -> Vec<usize> {
+        let (canonical, sym) = board.canonical();
+        match self.index.get(&(canonical, turn)) {
+            Some(&node) => self
+                .graph
+                .edges(node)
+                .map(|edge| Board::from_canonical(*edge.weight(), sym))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// The graph in Graphviz DOT format, with a mini-board for every position colored by
+    /// how the game ends with best play, and the cells played on the edges. With `root`
+    /// (a board and the side to move), only the positions reachable from it are written;
+    /// `None` if that position isn't in the graph. Boards are drawn in their canonical
+    /// orientation, as stored, so the root may appear rotated or mirrored.
+    pub fn to_dot(&self, root: Option<(&Board, Player)>) -> Option<String> {
+        let graph = match root {
+            None => self.graph.map(|_, node| node, |_, &cell| cell),
+            Some((board, turn)) => {
+                let (canonical, _) = board.canonical();
+                let &start = self.index.get(&(canonical, turn))?;
+                let mut reachable = HashSet::new();
+                let mut bfs = Bfs::new(&self.graph, start);
+                while let Some(node) = bfs.next(&self.graph) {
+                    reachable.insert(node);
+                }
+                self.graph.filter_map(
+                    |idx, node| reachable.contains(&idx).then_some(node),
+                    |_, &cell| Some(cell),
+                )
+            }
+        };
+
+        let dot = Dot::with_attr_getters(
+            &graph,
+            &[Config::GraphContentOnly],
+            &|_, _| String::new(),
+            &|graph, (idx, _)| {
+                let node = graph[idx];
+                format!(
+                    "fillcolor = \"{}\" tooltip = \"{}, {} to move\"",
+                    node.color(),
+                    node.key,
+                    node.turn.letter()
+                )
+            },
+        );
+        Some(format!(
+            "digraph {{\n    node [shape = box, style = filled, fontname = \"Courier\"]\n{}}}\n",
+            dot
+        ))
+    }
+}
@@ -1,306 +1,492 @@
-use crate::win_graph; // We will bridge to win_graph::Tile and win_graph::Player
 use log::trace;
-use petgraph::prelude::NodeIndex;
-use petgraph::visit::Bfs;
 use rand::prelude::ThreadRng;
 use rand::Rng;
 use slint::{Brush, Color, Model, SharedString, VecModel};
-use std::collections::HashSet;
+use std::collections::HashMap;
 use std::rc::Rc;
+use ttt_engine::analysis::{self, Analysis, Verdict};
+use ttt_engine::game::{Game, Variant};
+use ttt_engine::mode::GameMode;
+use ttt_engine::notation::{Coordinates, Notation};
+use ttt_engine::rules::{Mark, Move, Outcome, Player};
+use ttt_engine::strategy::Strategy;
 
 slint::include_modules!();
 
+const DEFAULT_COLOR: Brush = Brush::SolidColor(Color::from_rgb_u8(255, 255, 0));
 const HUMAN_WIN_COLOR: Brush = Brush::SolidColor(Color::from_rgb_u8(0, 140, 0));
 const MACHINE_WIN_COLOR: Brush = Brush::SolidColor(Color::from_rgb_u8(140, 0, 0));
-
-/// Local "which player" type, used by your UI logic.
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub enum Player {
-    Machine,
-    Human,
-    Nobody,
+const HINT_COLOR: Brush = Brush::SolidColor(Color::from_rgb_u8(100, 180, 255));
+/// Cells the side to move is restricted to, e.g. the active sub-board.
+const ACTIVE_COLOR: Brush = Brush::SolidColor(Color::from_rgb_u8(255, 170, 0));
+/// Lines that decided part of the board, e.g. claimed sub-boards.
+const HUMAN_LINE_COLOR: Brush = Brush::SolidColor(Color::from_rgb_u8(150, 210, 150));
+const MACHINE_LINE_COLOR: Brush = Brush::SolidColor(Color::from_rgb_u8(210, 150, 150));
+/// The last move of the position shown by the replay viewer.
+const LAST_MOVE_COLOR: Brush = Brush::SolidColor(Color::from_rgb_u8(190, 160, 255));
+
+/// Replays the `Sequence` model into the moves it records; unknown players are skipped.
+fn build_moves_from_model(sequence_model: &Rc<VecModel<Sequence>>) -> Vec<Move> {
+    sequence_model
+        .iter()
+        .filter_map(|seq| {
+            let player = Player::from_letter(seq.player.as_str())?;
+            let idx = usize::try_from(seq.id).ok()?;
+            let mv = Move::new(idx, player);
+            Some(match Mark::from_letter(seq.mark.as_str()) {
+                Some(mark) => mv.with_mark(mark),
+                None => mv,
+            })
+        })
+        .collect()
 }
 
-/// Local tile type for your UI logic.
-#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-pub struct Tile {
-    pub field_id: i32,
-    pub player: Player,
+/// The letter of the mark on `idx` for `TileData`; empty where the side's own symbol
+/// is drawn, or the cell is empty.
+fn mark_letter(game: &dyn Game, idx: usize) -> SharedString {
+    SharedString::from(game.mark(idx).map_or("", Mark::letter))
 }
 
-impl Tile {
-    /*
-        pub fn init() -> Self {
-            Self {
-                field_id: -1,
-                player: Player::Nobody,
-            }
-        }
-    */
-    pub fn new(field_id: i32, player: Player) -> Self {
-        Self { field_id, player }
+/// How the replay viewer words a verdict for the side that just moved.
+fn verdict_text(verdict: Verdict) -> &'static str {
+    match verdict {
+        Verdict::Win => "winning",
+        Verdict::Draw => "drawn",
+        Verdict::Loss => "losing",
+        Verdict::Unclear => "unclear",
     }
 }
 
-/// A constant list of winning tile combinations for quick local detection.
-const WIN_COMBINATIONS: [[i32; 3]; 8] = [
-    [0, 1, 2],
-    [3, 4, 5],
-    [6, 7, 8],
-    [0, 3, 6],
-    [1, 4, 7],
-    [2, 5, 8],
-    [0, 4, 8],
-    [2, 4, 6],
-];
-
-/// --------- BRIDGING TYPES TO/FROM `win_graph.rs` ---------
-use win_graph::Player as WinPlayer;
-use win_graph::Tile as WinTile;
-
-/// Convert our local `Player` to `win_graph::Player` (skip "Nobody").
-fn to_win_player(p: Player) -> Option<WinPlayer> {
-    match p {
-        Player::Machine => Some(WinPlayer::Machine),
-        Player::Human => Some(WinPlayer::Human),
-        Player::Nobody => None, // No equivalent in `win_graph`, so skip
-    }
-}
-
-/// Convert `win_graph::Player` back to local `Player` (we omit "Nobody").
-fn from_win_player(wp: WinPlayer) -> Player {
-    match wp {
-        WinPlayer::Machine => Player::Machine,
-        WinPlayer::Human => Player::Human,
-    }
+/// Replays the `Sequence` model into a position of `variant`.
+fn build_game(variant: Variant, sequence_model: &Rc<VecModel<Sequence>>) -> Box<dyn Game> {
+    variant.replay(&build_moves_from_model(sequence_model))
 }
 
-/// Convert our local `Tile` into a `win_graph::Tile` (skipping if "Nobody").
-fn to_win_tile(t: Tile) -> Option<WinTile> {
-    // If player is "Nobody," there's no valid move in win_graph.
-    let wplayer = to_win_player(t.player)?;
-    // If field_id < 0, skip as well.
-    if t.field_id < 0 {
-        return None;
-    }
-    Some(WinTile::new(t.field_id as usize, wplayer))
-}
+pub struct GameLogic;
 
-/// Convert a `win_graph::Tile` back to our local `Tile`.
-fn from_win_tile(wt: &WinTile) -> Tile {
-    let p = from_win_player(wt.player);
-    Tile::new(wt.idx as i32, p)
-}
+impl GameLogic {
+    /// Computes the next move for the side to move with `strategy`,
+    /// or `None` if the game is over.
+    pub fn search_next_step(
+        sequence_model: &Rc<VecModel<Sequence>>,
+        variant: Variant,
+        strategy: &mut dyn Strategy,
+    ) -> Option<Move> {
+        trace!("Sequence model size: {}", sequence_model.row_count());
 
-/// Compare two slices of `win_graph::Tile` ignoring order, by turning them into sets.
-fn tiles_equal_unordered_win(a: &[WinTile], b: &[WinTile]) -> bool {
-    if a.len() != b.len() {
-        return false;
+        // Rebuild the position from the recorded moves
+        let game = build_game(variant, sequence_model);
+        let turn = game.to_move();
+
+        let next_move = strategy.choose_move(game.as_ref())?;
+        trace!(
+            "{} picks tile {} ({:?}) for {:?}",
+            strategy.name(),
+            next_move.idx,
+            next_move.mark,
+            turn
+        );
+        Some(next_move)
     }
-    let set_a: HashSet<_> = a.iter().collect();
-    let set_b: HashSet<_> = b.iter().collect();
-    set_a == set_b
-}
 
-/// Build a `Vec<win_graph::Tile>` (WinTile) from the current game’s sequence model.
-/// We only convert moves that have a valid Machine/Human player. "Nobody" is ignored.
-fn build_win_tiles_from_model(sequence_model: &Rc<VecModel<Sequence>>) -> Vec<WinTile> {
-    let mut steps = Vec::new();
-    for seq in sequence_model.iter() {
-        let local_player = match seq.player.as_str() {
-            "M" => Player::Machine,
-            "H" => Player::Human,
-            _ => Player::Nobody,
-        };
-        let local_tile = Tile::new(seq.id, local_player);
-        if let Some(wt) = to_win_tile(local_tile) {
-            steps.push(wt);
+    /// Computes the state of the game shown in the UI and recolors the tiles:
+    /// the winning line, lines that decided part of the board, and the cells in play.
+    /// A hint stays highlighted unless one of those covers it.
+    pub fn game_outcome(
+        tiles_model: &Rc<VecModel<TileData>>,
+        sequence_model: &Rc<VecModel<Sequence>>,
+        variant: Variant,
+    ) -> Outcome {
+        let game = build_game(variant, sequence_model);
+        let outcome = game.outcome();
+
+        // Later entries win: the winning line is drawn over everything else
+        let mut colors: HashMap<usize, Brush> = HashMap::new();
+        if outcome == Outcome::InProgress {
+            for idx in game.active_cells() {
+                colors.insert(idx, ACTIVE_COLOR);
+            }
+        }
+        for (player, line) in game.decided_lines() {
+            let brush = match player {
+                Player::Machine => MACHINE_LINE_COLOR,
+                Player::Human => HUMAN_LINE_COLOR,
+            };
+            for idx in line {
+                colors.insert(idx, brush.clone());
+            }
+        }
+        if let Outcome::Win(player, line) = &outcome {
+            let brush = match player {
+                Player::Machine => MACHINE_WIN_COLOR,
+                Player::Human => HUMAN_WIN_COLOR,
+            };
+            for &idx in line {
+                colors.insert(idx, brush.clone());
+            }
         }
-    }
-    steps
-}
-
-/// Convert a `Vec<win_graph::Tile>` back to `Vec<Tile>`.
-fn from_win_tiles(seq: &[WinTile]) -> Vec<Tile> {
-    seq.iter().map(from_win_tile).collect()
-}
-
-/// ----------------------------------------------------------
 
-pub struct GameLogic;
+        for (i, mut tile_data) in tiles_model.iter().enumerate() {
+            let brush = match usize::try_from(tile_data.id)
+                .ok()
+                .and_then(|idx| colors.get(&idx))
+            {
+                Some(brush) => brush.clone(),
+                None if tile_data.win_color == HINT_COLOR => continue,
+                None => DEFAULT_COLOR,
+            };
+            if tile_data.win_color != brush {
+                tile_data.win_color = brush;
+                tiles_model.set_row_data(i, tile_data);
+            }
+        }
+        outcome
+    }
 
-impl GameLogic {
-    /// Computes the next move sequence for the Machine using `win_graph`.
-    /// 1) Convert our local board-sequence to `Vec<WinTile>` for BFS lookups.
-    /// 2) Find a matching key in `win_graph`’s map.
-    /// 3) Use BFS on the graph of string keys.
-    /// 4) If found, convert that resulting `Vec<WinTile>` back to our local `Vec<Tile>`.
-    /// 5) Otherwise do a random move, bridging again as needed.
-    pub fn search_next_step(
+    /// Finds the strongest move for the side to move and highlights its tile.
+    /// Returns the banner text telling how the game ends with best play from there
+    /// (and which mark to place, where the player picks it),
+    /// or `None` if the game is already over. On larger boards the verdict only covers
+    /// the search horizon until few cells are left.
+    pub fn show_hint(
         tiles_model: &Rc<VecModel<TileData>>,
         sequence_model: &Rc<VecModel<Sequence>>,
-    ) -> Vec<Tile> {
-        trace!("Sequence model size: {}", sequence_model.row_count());
+        variant: Variant,
+    ) -> Option<String> {
+        let game = build_game(variant, sequence_model);
+        let Analysis {
+            best_moves,
+            verdict,
+        } = analysis::analyze(game.as_ref());
+        if best_moves.is_empty() {
+            return None;
+        }
 
-        // 1) Convert current move sequence to WinTile
-        let mut actual_state_win = build_win_tiles_from_model(sequence_model);
+        let mut rng: ThreadRng = rand::rng();
+        let hint = best_moves[rng.random_range(0..best_moves.len())];
+        trace!("Hint for {:?}: {:?} ({:?})", game.to_move(), hint, verdict);
 
-        // 2) Load the big map + graph from win_graph.
-        let steps_map = win_graph::WinGraph::init_steps_map();
-        let graph = win_graph::WinGraph::build_graph();
+        for (i, mut tile_data) in tiles_model.iter().enumerate() {
+            if tile_data.id == hint.idx as i32 {
+                tile_data.win_color = HINT_COLOR;
+                tiles_model.set_row_data(i, tile_data);
+            }
+        }
 
-        let mut founded_key: Option<String> = None;
-        let mut next_state: Option<String> = None;
-        let mut rng: ThreadRng = rand::rng();
+        let text = match verdict {
+            Verdict::Win => "Hint: you can force a win",
+            Verdict::Draw => "Hint: best play is a draw",
+            Verdict::Unclear => "Hint: no forced result in sight",
+            Verdict::Loss => "Hint: your opponent can force a win",
+        };
+        Some(match hint.mark {
+            Some(mark) => format!("{} (place {})", text, mark.letter()),
+            None => text.to_string(),
+        })
+    }
 
-        // 3) Identify which key in the map corresponds to our "actual_state_win."
-        //    We'll do an unordered comparison: if steps_map[key] == actual_state_win, we found it.
-        for (key, tiles) in &steps_map {
-            if tiles_equal_unordered_win(tiles, &actual_state_win) {
-                founded_key = Some(key.clone());
-                trace!("Found matching key: {}", key);
-                break;
+    /// Removes the hint highlighting from every tile; `game_outcome` restores
+    /// any other highlighting of those tiles.
+    pub fn clear_hint(tiles_model: &Rc<VecModel<TileData>>) {
+        for (i, mut tile_data) in tiles_model.iter().enumerate() {
+            if tile_data.win_color == HINT_COLOR {
+                tile_data.win_color = DEFAULT_COLOR;
+                tiles_model.set_row_data(i, tile_data);
             }
         }
+    }
 
-        // 4) If found a key, BFS to find a child sequence
-        if let Some(ref current_key) = founded_key {
-            'outer: for start in graph.node_indices() {
-                let mut bfs = Bfs::new(&graph, start);
-                while let Some(nx) = bfs.next(&graph) {
-                    if graph[nx] == *current_key {
-                        let neighbors: Vec<NodeIndex> = graph.neighbors(nx).collect();
-                        trace!(
-                            "Found {} neighbor(s) for key {}",
-                            neighbors.len(),
-                            current_key
-                        );
-                        if !neighbors.is_empty() {
-                            let random_index = rng.random_range(0..neighbors.len());
-                            // This is the *next* state's string key
-                            next_state = Some(graph[neighbors[random_index]].clone());
-                        }
-                        break 'outer;
-                    }
-                }
+    /// Takes back the last move made by a person in `mode`, and the engine replies after it,
+    /// pushing them onto `redo_model` (top of the stack last).
+    /// Returns false if there is no such move to undo.
+    pub fn undo(
+        sequence_model: &Rc<VecModel<Sequence>>,
+        redo_model: &Rc<VecModel<Sequence>>,
+        mode: GameMode,
+    ) -> bool {
+        let by_person = |seq: &Sequence| {
+            Player::from_letter(seq.player.as_str()).is_some_and(|player| mode.is_person(player))
+        };
+        if !sequence_model.iter().any(|seq| by_person(&seq)) {
+            return false;
+        }
+
+        while let Some(last) = sequence_model.row_count().checked_sub(1) {
+            let seq = sequence_model.remove(last);
+            let was_person = by_person(&seq);
+            redo_model.push(seq);
+            if was_person {
+                break;
             }
         }
+        true
+    }
 
-        // 5) If we found a next_state key, return that sequence in *our* Tile type.
-        if let Some(ref key) = next_state {
-            trace!("Next state key: {}", key);
-            if let Some(seq_win_tiles) = steps_map.get(key) {
-                // Convert from WinTile -> local Tile
-                let seq_local = from_win_tiles(seq_win_tiles);
-                return seq_local;
+    /// Replays the person's move on top of `redo_model` and the engine replies that followed it.
+    /// Returns false if there is nothing to redo.
+    pub fn redo(
+        sequence_model: &Rc<VecModel<Sequence>>,
+        redo_model: &Rc<VecModel<Sequence>>,
+        mode: GameMode,
+    ) -> bool {
+        let Some(top) = redo_model.row_count().checked_sub(1) else {
+            return false;
+        };
+        sequence_model.push(redo_model.remove(top));
+
+        while let Some(top) = redo_model.row_count().checked_sub(1) {
+            let by_person = redo_model.row_data(top).is_some_and(|seq| {
+                Player::from_letter(seq.player.as_str())
+                    .is_some_and(|player| mode.is_person(player))
+            });
+            if by_person {
+                break;
             }
+            sequence_model.push(redo_model.remove(top));
         }
+        true
+    }
+
+    /// The moves recorded in the sequence, in the order they were played.
+    pub fn moves(sequence_model: &Rc<VecModel<Sequence>>) -> Vec<Move> {
+        build_moves_from_model(sequence_model)
+    }
+
+    /// True once the Human side has made a move in the sequence.
+    pub fn human_has_moved(sequence_model: &Rc<VecModel<Sequence>>) -> bool {
+        build_moves_from_model(sequence_model)
+            .iter()
+            .any(|mv| mv.player == Player::Human)
+    }
+
+    /// Replaces the sequence with `moves`, e.g. from a loaded record, and redraws the board.
+    /// The moves are expected to be legal in `variant`.
+    pub fn load_moves(
+        tiles_model: &Rc<VecModel<TileData>>,
+        sequence_model: &Rc<VecModel<Sequence>>,
+        variant: Variant,
+        moves: &[Move],
+    ) {
+        let sequence = moves.iter().map(|mv| Sequence {
+            id: mv.idx as i32,
+            player: SharedString::from(mv.player.letter()),
+            mark: SharedString::from(mv.mark.map_or("", Mark::letter)),
+        });
+        sequence_model.set_vec(sequence.collect::<Vec<_>>());
+        Self::sync_tiles(tiles_model, sequence_model, variant);
+    }
 
-        // 6) Otherwise, do a random move.
-        trace!("No BFS match found; picking random empty tile");
-        let empty_tile_ids: Vec<i32> = tiles_model
+    /// The move list of the replay viewer, every move in algebraic notation. The moves
+    /// are judged one at a time by `judge_move`, as the viewer shows them.
+    pub fn replay_moves(
+        sequence_model: &Rc<VecModel<Sequence>>,
+        variant: Variant,
+    ) -> Vec<ReplayMove> {
+        let coordinates = Coordinates::new(variant.new_game(Player::Human).as_ref());
+        build_moves_from_model(sequence_model)
             .iter()
-            .filter(|tile| tile.empty && !tile.machine_clicked && !tile.human_clicked)
-            .map(|td| td.id)
-            .collect();
+            .enumerate()
+            .map(|(ply, mv)| ReplayMove {
+                text: format!(
+                    "{}. {}",
+                    ply + 1,
+                    coordinates.format_move(mv, Notation::Algebraic)
+                )
+                .into(),
+                verdict: SharedString::default(),
+                blunder: false,
+                judged: false,
+            })
+            .collect()
+    }
 
-        if empty_tile_ids.is_empty() {
-            // No moves left
-            return from_win_tiles(&actual_state_win);
+    /// Fills in the engine's verdict after move `index` of the sequence, flagging it
+    /// as a blunder if it changed the result under best play for the worse.
+    pub fn judge_move(
+        sequence_model: &Rc<VecModel<Sequence>>,
+        variant: Variant,
+        index: usize,
+        entry: &mut ReplayMove,
+    ) {
+        let moves = build_moves_from_model(sequence_model);
+        let Some(review) = analysis::review_move(variant, &moves, index) else {
+            return;
+        };
+        trace!("Move {}: {:?}", index + 1, review);
+        entry.verdict = if review.is_blunder() {
+            format!(
+                "{}, blunder (was {})",
+                verdict_text(review.after),
+                verdict_text(review.before)
+            )
+        } else {
+            verdict_text(review.after).to_string()
         }
+        .into();
+        entry.blunder = review.is_blunder();
+        entry.judged = true;
+    }
 
-        let rnd_tile_id = empty_tile_ids[rng.random_range(0..empty_tile_ids.len())];
-        trace!("Machine picks random tile {}", rnd_tile_id);
-
-        // Add that random move to our *win_graph* style state:
-        // (We convert "Machine" -> WinPlayer::Machine, i32->usize)
-        actual_state_win.push(win_graph::Tile::new(
-            rnd_tile_id as usize,
-            WinPlayer::Machine,
-        ));
-
-        // Try to see if that updated "actual_state_win" matches a known key in steps_map
-        for (key, tiles) in &steps_map {
-            if tiles_equal_unordered_win(tiles, &actual_state_win) {
-                trace!("Found next step with key: {}", key);
-                // Convert from WinTile -> local Tile
-                let seq_local = from_win_tiles(tiles);
-                return seq_local;
+    /// Redraws the board as it stood after the first `ply` moves of the sequence,
+    /// with the last of them highlighted. The sequence itself is left as it is.
+    pub fn show_ply(
+        tiles_model: &Rc<VecModel<TileData>>,
+        sequence_model: &Rc<VecModel<Sequence>>,
+        variant: Variant,
+        ply: usize,
+    ) {
+        let played: Vec<Sequence> = sequence_model.iter().take(ply).collect();
+        let last = played.last().map(|seq| seq.id);
+        let played_model = Rc::new(VecModel::from(played));
+        Self::sync_tiles(tiles_model, &played_model, variant);
+        Self::game_outcome(tiles_model, &played_model, variant);
+
+        // The winning line stays as it is
+        for (i, mut tile_data) in tiles_model.iter().enumerate() {
+            let on_winning_line =
+                tile_data.win_color == HUMAN_WIN_COLOR || tile_data.win_color == MACHINE_WIN_COLOR;
+            if Some(tile_data.id) == last && !on_winning_line {
+                tile_data.win_color = LAST_MOVE_COLOR;
+                tiles_model.set_row_data(i, tile_data);
             }
         }
+    }
+
+    /// The side whose turn it is: the opponent of the last mover, or the Human side
+    /// on an empty board (the Machine makes its opening move itself).
+    pub fn side_to_move(sequence_model: &Rc<VecModel<Sequence>>) -> Player {
+        build_moves_from_model(sequence_model)
+            .last()
+            .map_or(Player::Human, |mv| mv.player.opponent())
+    }
+
+    /// Marks the tile of `mv` for its player and records the move in the sequence.
+    /// Returns false (and changes nothing) if `variant` doesn't allow the move,
+    /// e.g. the tile is taken or it is the other side's turn.
+    pub fn apply_move(
+        tiles_model: &Rc<VecModel<TileData>>,
+        sequence_model: &Rc<VecModel<Sequence>>,
+        variant: Variant,
+        mv: Move,
+    ) -> bool {
+        // Either side may open; after that the turns alternate
+        let game = if sequence_model.row_count() == 0 {
+            variant.new_game(mv.player)
+        } else {
+            build_game(variant, sequence_model)
+        };
+        if !game.legal_moves().contains(&mv) {
+            return false;
+        }
+        let mut after = game.clone_box();
+        after.play(mv);
 
-        // If even that didn't match, just return the local version of `actual_state_win`.
-        from_win_tiles(&actual_state_win)
+        let id = mv.idx as i32;
+        let Some((index, mut tile_data)) = tiles_model
+            .iter()
+            .enumerate()
+            .find(|(_, t)| t.id == id && t.empty)
+        else {
+            return false;
+        };
+
+        tile_data.machine_clicked = mv.player == Player::Machine;
+        tile_data.human_clicked = mv.player == Player::Human;
+        tile_data.mark = mark_letter(after.as_ref(), mv.idx);
+        tile_data.empty = false;
+        tiles_model.set_row_data(index, tile_data);
+
+        sequence_model.push(Sequence {
+            id,
+            player: SharedString::from(mv.player.letter()),
+            mark: SharedString::from(mv.mark.map_or("", Mark::letter)),
+        });
+        true
     }
 
-    /// Checks if the given player has a 3-in-a-row, for local highlighting logic.
-    pub fn get_win_combos(tiles_model: &Rc<VecModel<TileData>>, player: Player) -> Vec<i32> {
-        let is_claimed_by = |tile: &TileData| {
-            !tile.empty
-                && match player {
-                    Player::Machine => tile.machine_clicked,
-                    Player::Human => tile.human_clicked,
-                    Player::Nobody => false,
+    /// Rebuilds every tile from the moves in the sequence, clearing old win colors.
+    /// Lays the tiles out afresh if the variant has a different number of cells.
+    pub fn sync_tiles(
+        tiles_model: &Rc<VecModel<TileData>>,
+        sequence_model: &Rc<VecModel<Sequence>>,
+        variant: Variant,
+    ) {
+        let game = build_game(variant, sequence_model);
+        if tiles_model.row_count() != game.cell_count() {
+            let tiles = (0..game.cell_count()).map(|idx| {
+                let (col, row) = game.cell_position(idx);
+                TileData {
+                    id: idx as i32,
+                    col,
+                    row,
+                    win_color: DEFAULT_COLOR,
+                    human_clicked: false,
+                    machine_clicked: false,
+                    mark: SharedString::default(),
+                    empty: true,
                 }
-        };
+            });
+            tiles_model.set_vec(tiles.collect::<Vec<_>>());
+        }
 
-        for combo in WIN_COMBINATIONS {
-            if combo.iter().all(|&id| {
-                tiles_model
-                    .iter()
-                    .any(|tile_data| tile_data.id == id && is_claimed_by(&tile_data))
-            }) {
-                return combo.to_vec();
-            }
+        for (index, mut tile_data) in tiles_model.iter().enumerate() {
+            let idx = usize::try_from(tile_data.id).ok();
+            let player = idx.and_then(|idx| game.cell(idx));
+            tile_data.machine_clicked = player == Some(Player::Machine);
+            tile_data.human_clicked = player == Some(Player::Human);
+            tile_data.mark =
+                idx.map_or_else(SharedString::default, |idx| mark_letter(game.as_ref(), idx));
+            tile_data.empty = player.is_none();
+            tile_data.win_color = DEFAULT_COLOR;
+            tiles_model.set_row_data(index, tile_data);
         }
-        Vec::new()
     }
 
-    /// True if either Machine or Human has a winning line; also updates tile colors in the UI.
-    pub fn has_winner(tiles_model: &Rc<VecModel<TileData>>) -> bool {
-        for (player, brush) in &[
-            (Player::Machine, MACHINE_WIN_COLOR),
-            (Player::Human, HUMAN_WIN_COLOR),
-        ] {
-            let combo = Self::get_win_combos(tiles_model, *player);
-            if !combo.is_empty() {
-                // Color the winning tiles
-                for (i, mut tile_data) in tiles_model.iter().enumerate() {
-                    if combo.contains(&tile_data.id)
-                        && ((tile_data.machine_clicked && *player == Player::Machine)
-                            || (tile_data.human_clicked && *player == Player::Human))
-                    {
-                        tile_data.win_color = brush.clone();
-                        tiles_model.set_row_data(i, tile_data);
-                    }
-                }
-                return true;
-            }
+    /// Resets every tile and the sequence for a game of `variant`,
+    /// then lets the Machine open if it moves first.
+    pub fn new_game(
+        tiles_model: &Rc<VecModel<TileData>>,
+        sequence_model: &Rc<VecModel<Sequence>>,
+        first_player: Player,
+        variant: Variant,
+    ) {
+        for i in (0..sequence_model.row_count()).rev() {
+            sequence_model.remove(i);
+        }
+        Self::sync_tiles(tiles_model, sequence_model, variant);
+
+        if first_player == Player::Machine {
+            Self::random_machine_start(tiles_model, sequence_model, variant);
         }
-        false
     }
 
-    /// Clears the sequence and places a random opening move for the Machine (tile 4 or 0).
+    /// Clears the sequence and places a random opening move for the Machine
+    /// (tile 4 or 0 on the classic board, a center tile on larger ones).
     pub fn random_machine_start(
         tiles_model: &Rc<VecModel<TileData>>,
         sequence_model: &Rc<VecModel<Sequence>>,
+        variant: Variant,
     ) {
         // Clear any existing moves
         for i in (0..sequence_model.row_count()).rev() {
             sequence_model.remove(i);
         }
 
-        // Choose center or top-left corner
+        // Choose one of the variant's opening moves
+        let mut game = variant.new_game(Player::Machine);
+        let openings = game.opening_moves();
         let mut rng = rand::rng();
-        let first_move = if rng.random_range(0..2) == 1 { 4 } else { 0 };
+        let first_move = openings[rng.random_range(0..openings.len())];
+        game.play(first_move);
 
         // Mark tile in the UI
         if let Some((idx, mut tile_data)) = tiles_model
             .iter()
             .enumerate()
-            .find(|(_, t)| t.id == first_move)
+            .find(|(_, t)| t.id == first_move.idx as i32)
         {
             tile_data.machine_clicked = true;
+            tile_data.mark = mark_letter(game.as_ref(), first_move.idx);
             tile_data.empty = false;
             tiles_model.set_row_data(idx, tile_data);
         }
@@ -309,8 +495,9 @@ impl GameLogic {
         sequence_model.insert(
             0,
             Sequence {
-                id: first_move,
-                player: SharedString::from("M"),
+                id: first_move.idx as i32,
+                player: SharedString::from(Player::Machine.letter()),
+                mark: SharedString::from(first_move.mark.map_or("", Mark::letter)),
             },
         );
     }
@@ -1,24 +1,506 @@
 mod game_logic;
-mod win_graph;
+mod storage;
 
-use game_logic::{AppWindow, GameLogic, Sequence, TileData};
+use game_logic::{AppWindow, GameLogic, ScoreRow, Sequence, TileData};
 use log::{info, trace};
-use slint::{Brush, Color, ComponentHandle, SharedString};
+use slint::{CloseRequestResponse, ComponentHandle, Timer, TimerMode};
 use slint::{Model, ModelNotify, VecModel};
-use std::panic;
 use std::process::exit;
 use std::rc::Rc;
+use std::time::Duration;
+use ttt_engine::game::{Marks, Variant};
+use ttt_engine::mode::GameMode;
+use ttt_engine::notation::Notation;
+use ttt_engine::record::GameRecord;
+use ttt_engine::rules::{Mark, Move, Outcome, Player};
+use ttt_engine::selfplay;
+use ttt_engine::stats::{GameEntry, GameResult, Statistics, Tally};
+use ttt_engine::strategy::{self, strategy_by_index};
 
-const DEFAULT_COLOR: Brush = Brush::SolidColor(Color::from_rgb_u8(255, 255, 0));
+/// Name the statistics are stored under in the data directory, see `storage`.
+const STATS_NAME: &str = "ttt-stats.txt";
+/// Name the game and settings are kept under between runs of the app.
+const SESSION_NAME: &str = "ttt-session.ttt";
+
+/// Who opens the game, from the "first mover" selector in the UI.
+/// Two people sharing the board always start with Player 1 (the Human side).
+fn first_player(ui: &AppWindow) -> Player {
+    if ui.get_first_mover() == 1 || game_mode(ui) == GameMode::Hotseat {
+        Player::Human
+    } else {
+        Player::Machine
+    }
+}
+
+fn game_mode(ui: &AppWindow) -> GameMode {
+    GameMode::from_index(ui.get_game_mode())
+}
+
+fn variant(ui: &AppWindow) -> Variant {
+    Variant::from_index(ui.get_variant())
+}
+
+/// Sizes the board area of the window for the selected variant, and picks the marks.
+fn show_variant(ui: &AppWindow) {
+    let game = variant(ui).new_game(Player::Human);
+    let (columns, rows) = game.grid_size();
+    ui.set_grid_columns(columns);
+    ui.set_grid_rows(rows);
+    ui.set_side_marks(game.marks() == Marks::PerSide);
+    ui.set_choose_mark(game.marks() == Marks::Chosen);
+}
+
+/// The move a click on tile `id` makes for `side`, with the mark from the
+/// "Place X / Place O" selector in variants where the player picks it.
+fn clicked_move(ui: &AppWindow, id: i32, side: Player) -> Move {
+    let mv = Move::new(id as usize, side);
+    if !ui.get_choose_mark() {
+        return mv;
+    }
+    mv.with_mark(if ui.get_selected_mark() == 1 {
+        Mark::O
+    } else {
+        Mark::X
+    })
+}
+
+/// Shows the banner (result or turn) and locks the board once the game is over.
+/// A game against the engine that just finished goes into the statistics.
+fn show_outcome(ui: &AppWindow, outcome: &Outcome, sequence_model: &Rc<VecModel<Sequence>>) {
+    let to_move = GameLogic::side_to_move(sequence_model);
+    ui.set_game_over(*outcome != Outcome::InProgress);
+    ui.set_human_moved(GameLogic::human_has_moved(sequence_model));
+    ui.set_status_text(game_mode(ui).status_text(outcome, to_move).into());
+    record_result(ui, outcome, sequence_model);
+
+    // A browser doesn't reliably tell a page it is being closed, so the web version keeps
+    // the session up to date after every change instead of saving it on exit
+    #[cfg(target_arch = "wasm32")]
+    save_session(ui, sequence_model);
+}
+
+/// The statistics stored so far; empty if there are none yet or they can't be read.
+fn load_statistics() -> Statistics {
+    match storage::load_data(STATS_NAME) {
+        Ok(text) => Statistics::parse(&text),
+        Err(error) => {
+            info!("No statistics loaded from {}: {}", STATS_NAME, error);
+            Statistics::default()
+        }
+    }
+}
+
+/// Adds a finished game against the engine to the statistics, once per game:
+/// taking moves back and finishing again doesn't count a second time.
+fn record_result(ui: &AppWindow, outcome: &Outcome, sequence_model: &Rc<VecModel<Sequence>>) {
+    if game_mode(ui) != GameMode::VsMachine || ui.get_result_recorded() {
+        return;
+    }
+    let result = match outcome {
+        Outcome::InProgress => return,
+        Outcome::Win(Player::Human, _) => GameResult::Win,
+        Outcome::Win(Player::Machine, _) => GameResult::Loss,
+        Outcome::Draw => GameResult::Draw,
+    };
+    ui.set_result_recorded(true);
+    let first_player = GameLogic::moves(sequence_model)
+        .first()
+        .map_or(Player::Human, |mv| mv.player);
+
+    let mut statistics = load_statistics();
+    statistics.record(GameEntry {
+        date: storage::today(),
+        variant: variant(ui).name(),
+        difficulty: strategy_by_index(ui.get_difficulty()).name().to_string(),
+        first_player,
+        result,
+    });
+    if let Err(error) = storage::save_data(STATS_NAME, &statistics.write()) {
+        info!("Can't save the statistics to {}: {}", STATS_NAME, error);
+    }
+    show_statistics(ui, &statistics);
+}
+
+/// Fills the scoreboard: all games first, then every variant, difficulty and first mover.
+fn show_statistics(ui: &AppWindow, statistics: &Statistics) {
+    let score_row = |label: String, tally: &Tally| {
+        let streak = match tally.streak {
+            Some((result, length)) => {
+                let name = match (result, length) {
+                    (GameResult::Loss, 1) => "loss".to_string(),
+                    (GameResult::Loss, _) => "losses".to_string(),
+                    (result, 1) => result.name().to_string(),
+                    (result, _) => format!("{}s", result.name()),
+                };
+                format!("{} {} in a row", length, name)
+            }
+            None => "No games yet".to_string(),
+        };
+        ScoreRow {
+            label: label.into(),
+            record: format!(
+                "{} won, {} lost, {} drawn: {:.0}% won",
+                tally.wins,
+                tally.losses,
+                tally.draws,
+                tally.win_rate() * 100.0
+            )
+            .into(),
+            streaks: format!("{}, best winning streak {}", streak, tally.best_win_streak).into(),
+        }
+    };
+
+    let mut rows = vec![score_row("All games".to_string(), &statistics.total())];
+    for (entry, tally) in statistics.tallies() {
+        let first = match entry.first_player {
+            Player::Human => "you first",
+            Player::Machine => "machine first",
+        };
+        let label = format!("{} vs {}, {}", entry.variant, entry.difficulty, first);
+        rows.push(score_row(label, &tally));
+    }
+    ui.set_scoreboard(Rc::new(VecModel::from(rows)).into());
+}
+
+/// Steps back (undo) or forward (redo) through the move history and redraws the board.
+fn step_history(ui: &AppWindow, forward: bool) {
+    let ttt_tiles: Vec<TileData> = ui.get_ttt_tiles().iter().collect();
+    let tiles_model = Rc::new(VecModel::from(ttt_tiles));
+    let sequence: Vec<Sequence> = ui.get_sequence().iter().collect();
+    let sequence_model = Rc::new(VecModel::from(sequence));
+    let redo: Vec<Sequence> = ui.get_redo_sequence().iter().collect();
+    let redo_model = Rc::new(VecModel::from(redo));
+
+    let mode = game_mode(ui);
+    let changed = if forward {
+        GameLogic::redo(&sequence_model, &redo_model, mode)
+    } else {
+        GameLogic::undo(&sequence_model, &redo_model, mode)
+    };
+    if !changed {
+        trace!("Nothing to {}", if forward { "redo" } else { "undo" });
+        return;
+    }
+
+    // Rebuild the board from the restored sequence, then recompute the highlighting
+    let variant = variant(ui);
+    GameLogic::sync_tiles(&tiles_model, &sequence_model, variant);
+    show_outcome(
+        ui,
+        &GameLogic::game_outcome(&tiles_model, &sequence_model, variant),
+        &sequence_model,
+    );
+
+    ui.set_redo_sequence(redo_model.into());
+    ui.set_sequence(sequence_model.into());
+    ui.set_ttt_tiles(tiles_model.into());
+}
+
+/// Who plays `player`'s side, for game records.
+fn player_name(ui: &AppWindow, player: Player) -> String {
+    game_mode(ui).player_name(
+        player,
+        strategy_by_index(ui.get_difficulty()).name(),
+        strategy_by_index(ui.get_opponent_strategy()).name(),
+    )
+}
+
+/// Writes the game so far as a record under the name typed in the UI.
+fn save_game(ui: &AppWindow) {
+    let sequence: Vec<Sequence> = ui.get_sequence().iter().collect();
+    let sequence_model = Rc::new(VecModel::from(sequence));
+    let mut record = GameRecord::new(variant(ui), GameLogic::moves(&sequence_model));
+    record.human = player_name(ui, Player::Human);
+    record.machine = player_name(ui, Player::Machine);
+    record.date = storage::today();
+
+    let name = ui.get_record_name();
+    match storage::save(&name, &record.write(Notation::Algebraic)) {
+        Ok(()) => {
+            info!("Saved {} moves as {}", record.moves.len(), name);
+            ui.set_status_text(format!("Saved as {}", name).into());
+        }
+        Err(error) => ui.set_status_text(format!("Can't save {}: {}", name, error).into()),
+    }
+}
+
+/// Loads the record named in the UI to continue it.
+fn load_game(ui: &AppWindow, timer: &Rc<Timer>) {
+    let name = ui.get_record_name();
+    let record = match storage::load(&name)
+        .and_then(|text| GameRecord::parse(&text).map_err(|error| error.to_string()))
+    {
+        Ok(record) => record,
+        Err(error) => {
+            ui.set_status_text(format!("Can't load {}: {}", name, error).into());
+            return;
+        }
+    };
+    info!(
+        "Loaded {} moves of {} from {}",
+        record.moves.len(),
+        record.variant.name(),
+        name
+    );
+    continue_game(ui, timer, &record);
+}
+
+/// Sets the board up to continue from the last move of `record`, switching to the variant
+/// it was played in. The engine replies right away if it is on move, and a self-play game
+/// carries on.
+fn continue_game(ui: &AppWindow, timer: &Rc<Timer>, record: &GameRecord) {
+    timer.stop();
+    // A game that was over when saved has been counted already
+    ui.set_result_recorded(record.game().outcome() != Outcome::InProgress);
+
+    ui.set_variant(record.variant.index());
+    if let Some(first) = record.moves.first() {
+        ui.set_first_mover(i32::from(first.player == Player::Human));
+    }
+    show_variant(ui);
+    let ttt_tiles: Vec<TileData> = ui.get_ttt_tiles().iter().collect();
+    let tiles_model = Rc::new(VecModel::from(ttt_tiles));
+    let sequence_model = Rc::new(VecModel::<Sequence>::default());
+    let variant = record.variant;
+    GameLogic::load_moves(&tiles_model, &sequence_model, variant, &record.moves);
+
+    let mode = game_mode(ui);
+    let mut outcome = GameLogic::game_outcome(&tiles_model, &sequence_model, variant);
+    let to_move = GameLogic::side_to_move(&sequence_model);
+    if outcome == Outcome::InProgress && mode == GameMode::VsMachine && !mode.is_person(to_move) {
+        let mut strategy = strategy_by_index(ui.get_difficulty());
+        if let Some(mv) = GameLogic::search_next_step(&sequence_model, variant, strategy.as_mut()) {
+            GameLogic::apply_move(&tiles_model, &sequence_model, variant, mv);
+        }
+        outcome = GameLogic::game_outcome(&tiles_model, &sequence_model, variant);
+    }
+
+    show_outcome(ui, &outcome, &sequence_model);
+    ui.set_redo_sequence(Rc::new(VecModel::<Sequence>::default()).into());
+    ui.set_sequence(sequence_model.into());
+    ui.set_ttt_tiles(tiles_model.into());
+    if mode == GameMode::SelfPlay && outcome == Outcome::InProgress {
+        start_self_play(ui, timer);
+    }
+}
+
+/// Stores the game and the settings, to be offered for resuming on the next start.
+/// Nothing is stored while that offer is still open, so it isn't lost by quitting again.
+fn save_session(ui: &AppWindow, sequence_model: &Rc<VecModel<Sequence>>) {
+    if ui.get_resume_offered() {
+        return;
+    }
+    let mut record = GameRecord::new(variant(ui), GameLogic::moves(sequence_model));
+    record.human = player_name(ui, Player::Human);
+    record.machine = player_name(ui, Player::Machine);
+    record.date = storage::today();
+    record.tags = [
+        ("Mode", ui.get_game_mode()),
+        ("Difficulty", ui.get_difficulty()),
+        ("Opponent", ui.get_opponent_strategy()),
+        ("FirstMover", ui.get_first_mover()),
+        ("Symbol", ui.get_human_symbol()),
+        ("PlaceMark", ui.get_selected_mark()),
+        ("SelfPlayDelay", ui.get_self_play_delay()),
+    ]
+    .into_iter()
+    .map(|(name, value)| (name.to_string(), value.to_string()))
+    .collect();
+    match storage::save_data(SESSION_NAME, &record.write(Notation::Index)) {
+        Ok(()) => trace!("Session saved with {} moves", record.moves.len()),
+        Err(error) => info!("Can't save the session to {}: {}", SESSION_NAME, error),
+    }
+}
+
+/// The session stored by `save_session`, with its settings applied to the UI.
+fn restore_session(ui: &AppWindow) -> Option<GameRecord> {
+    let text = storage::load_data(SESSION_NAME).ok()?;
+    let record = match GameRecord::parse(&text) {
+        Ok(record) => record,
+        Err(error) => {
+            info!("Ignoring the session in {}: {}", SESSION_NAME, error);
+            return None;
+        }
+    };
+    ui.set_variant(record.variant.index());
+    for (name, value) in &record.tags {
+        let Ok(value) = value.parse::<i32>() else {
+            continue;
+        };
+        match name.as_str() {
+            "Mode" => ui.set_game_mode(value),
+            "Difficulty" => ui.set_difficulty(value),
+            "Opponent" => ui.set_opponent_strategy(value),
+            "FirstMover" => ui.set_first_mover(value),
+            "Symbol" => ui.set_human_symbol(value),
+            "PlaceMark" => ui.set_selected_mark(value),
+            "SelfPlayDelay" => ui.set_self_play_delay(value),
+            _ => {}
+        }
+    }
+    Some(record)
+}
+
+/// Opens the replay viewer on the finished game, or closes it and shows the final
+/// position again.
+fn toggle_replay(ui: &AppWindow) {
+    let ttt_tiles: Vec<TileData> = ui.get_ttt_tiles().iter().collect();
+    let tiles_model = Rc::new(VecModel::from(ttt_tiles));
+    let sequence: Vec<Sequence> = ui.get_sequence().iter().collect();
+    let sequence_model = Rc::new(VecModel::from(sequence));
+    let variant = variant(ui);
+
+    if ui.get_replaying() {
+        trace!("Leaving the replay viewer");
+        ui.set_replaying(false);
+        GameLogic::sync_tiles(&tiles_model, &sequence_model, variant);
+        let outcome = GameLogic::game_outcome(&tiles_model, &sequence_model, variant);
+        show_outcome(ui, &outcome, &sequence_model);
+        ui.set_ttt_tiles(tiles_model.into());
+        return;
+    }
+
+    let replay_moves = GameLogic::replay_moves(&sequence_model, variant);
+    let last_ply = replay_moves.len() as i32;
+    ui.set_replay_moves(Rc::new(VecModel::from(replay_moves)).into());
+    ui.set_replaying(true);
+    replay_to(ui, last_ply);
+}
+
+/// Shows the position after `ply` moves in the replay viewer, and the verdict on the last
+/// one. Each move is judged the first time it is shown, so only that one waits for the engine.
+fn replay_to(ui: &AppWindow, ply: i32) {
+    let replay_moves = ui.get_replay_moves();
+    let ply = ply.clamp(0, replay_moves.row_count() as i32);
+    let ttt_tiles: Vec<TileData> = ui.get_ttt_tiles().iter().collect();
+    let tiles_model = Rc::new(VecModel::from(ttt_tiles));
+    let sequence: Vec<Sequence> = ui.get_sequence().iter().collect();
+    let sequence_model = Rc::new(VecModel::from(sequence));
+    let variant = variant(ui);
+    GameLogic::show_ply(&tiles_model, &sequence_model, variant, ply as usize);
+
+    let last = ply.checked_sub(1).map(|index| index as usize);
+    let status = match last.and_then(|index| Some((index, replay_moves.row_data(index)?))) {
+        Some((index, mut entry)) => {
+            if !entry.judged {
+                GameLogic::judge_move(&sequence_model, variant, index, &mut entry);
+                replay_moves.set_row_data(index, entry.clone());
+            }
+            format!("{}: {}", entry.text, entry.verdict)
+        }
+        None => format!("Start, {} moves to replay", replay_moves.row_count()),
+    };
+    ui.set_status_text(status.into());
+    ui.set_replay_ply(ply);
+    ui.set_ttt_tiles(tiles_model.into());
+}
+
+/// Plays one move of a self-play game with the strategy selected for the side to move.
+/// Returns false once the game is over.
+fn self_play_step(ui: &AppWindow) -> bool {
+    let ttt_tiles: Vec<TileData> = ui.get_ttt_tiles().iter().collect();
+    let tiles_model = Rc::new(VecModel::from(ttt_tiles));
+    let sequence: Vec<Sequence> = ui.get_sequence().iter().collect();
+    let sequence_model = Rc::new(VecModel::from(sequence));
+
+    let variant = variant(ui);
+    if GameLogic::game_outcome(&tiles_model, &sequence_model, variant) == Outcome::InProgress {
+        let mut strategy = match GameLogic::side_to_move(&sequence_model) {
+            Player::Human => strategy_by_index(ui.get_opponent_strategy()),
+            Player::Machine => strategy_by_index(ui.get_difficulty()),
+        };
+        if let Some(mv) = GameLogic::search_next_step(&sequence_model, variant, strategy.as_mut()) {
+            info!("{} moves on tile id: {:?}", strategy.name(), mv.idx);
+            GameLogic::apply_move(&tiles_model, &sequence_model, variant, mv);
+        }
+    }
+
+    let outcome = GameLogic::game_outcome(&tiles_model, &sequence_model, variant);
+    show_outcome(ui, &outcome, &sequence_model);
+    ui.set_sequence(sequence_model.into());
+    ui.set_ttt_tiles(tiles_model.into());
+    outcome == Outcome::InProgress
+}
+
+/// Starts animating a self-play game, one move per tick of the speed slider's delay.
+fn start_self_play(ui: &AppWindow, timer: &Rc<Timer>) {
+    let ui_weak = ui.as_weak();
+    let timer_weak = Rc::downgrade(timer);
+    timer.start(TimerMode::Repeated, self_play_interval(ui), move || {
+        let Some(ui) = ui_weak.upgrade() else {
+            return;
+        };
+        if !self_play_step(&ui) {
+            trace!("Self-play game finished");
+            if let Some(timer) = timer_weak.upgrade() {
+                timer.stop();
+            }
+        }
+    });
+}
+
+fn self_play_interval(ui: &AppWindow) -> Duration {
+    Duration::from_millis(ui.get_self_play_delay().max(1) as u64)
+}
+
+/// `ttt-rs --self-play <strategy> <strategy> [games]` plays a match without the UI
+/// and prints the tally. Returns false if the arguments don't ask for it.
+#[cfg(not(target_arch = "wasm32"))]
+fn run_headless_self_play() -> bool {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) != Some("--self-play") {
+        return false;
+    }
+
+    let names: Vec<&str> = strategy::all_strategies()
+        .iter()
+        .map(|strategy| strategy.name())
+        .collect();
+    let usage = format!(
+        "usage: ttt-rs --self-play <strategy> <strategy> [games]\nstrategies: {}",
+        names.join(", ")
+    );
+    let (Some(mut first), Some(mut second)) = (
+        args.get(1)
+            .and_then(|name| strategy::strategy_by_name(name)),
+        args.get(2)
+            .and_then(|name| strategy::strategy_by_name(name)),
+    ) else {
+        eprintln!("{}", usage);
+        exit(2);
+    };
+    let Ok(games) = args.get(3).map_or(Ok(100), |games| games.parse::<u32>()) else {
+        eprintln!("{}", usage);
+        exit(2);
+    };
+
+    let result = selfplay::play_match(Variant::default(), first.as_mut(), second.as_mut(), games);
+    println!(
+        "{} vs {} over {} games: {} wins, {} losses, {} draws",
+        first.name(),
+        second.name(),
+        games,
+        result.wins,
+        result.losses,
+        result.draws
+    );
+    true
+}
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen::prelude::wasm_bindgen(start))]
-fn main() {
+pub fn main() {
     // So any panic prints a backtrace in the JS console (when using wasm).
-    panic::set_hook(Box::new(console_error_panic_hook::hook));
+    #[cfg(target_arch = "wasm32")]
+    std::panic::set_hook(Box::new(console_error_panic_hook::hook));
 
     // Initialize logger, so trace/info calls appear in debug console
     env_logger::init();
 
+    #[cfg(not(target_arch = "wasm32"))]
+    if run_headless_self_play() {
+        return;
+    }
+
     // Create the main window from Slint
     let ui = AppWindow::new().unwrap();
     let ui_weak = ui.as_weak();
@@ -32,10 +514,27 @@ fn main() {
     let sequence: Vec<Sequence> = ui.get_sequence().iter().collect();
     let sequence_model = Rc::new(VecModel::from(sequence));
 
-    // Machine starts the game with a random move
-    GameLogic::random_machine_start(&tiles_model, &sequence_model);
+    show_statistics(&ui, &load_statistics());
+
+    // Settings are back as they were left; an unfinished game is offered for resuming
+    if let Some(session) = restore_session(&ui) {
+        let unfinished = session.game().outcome() == Outcome::InProgress;
+        ui.set_resume_offered(unfinished && !session.moves.is_empty());
+    }
+
+    // Lay out the tiles; the Machine starts the game with a random move,
+    // unless the Human chose to open
+    show_variant(&ui);
+    GameLogic::new_game(
+        &tiles_model,
+        &sequence_model,
+        first_player(&ui),
+        variant(&ui),
+    );
 
     // Push the updated models back to the UI
+    let outcome = GameLogic::game_outcome(&tiles_model, &sequence_model, variant(&ui));
+    show_outcome(&ui, &outcome, &sequence_model);
     ui.set_ttt_tiles(tiles_model.clone().into());
     ui.set_sequence(sequence_model.clone().into());
 
@@ -44,87 +543,101 @@ fn main() {
         trace!("Selected id: {}", id);
         let ui = ui_weak.unwrap();
 
-        // Reload current tile states from UI
+        // Reload current tile states and move sequence from UI,
+        // the restart handler replaces both models
         let ttt_tiles: Vec<TileData> = ui.get_ttt_tiles().iter().collect();
         let tiles_model = Rc::new(VecModel::from(ttt_tiles));
+        let sequence: Vec<Sequence> = ui.get_sequence().iter().collect();
+        let sequence_model = Rc::new(VecModel::from(sequence));
 
-        if GameLogic::has_winner(&tiles_model) {
-            trace!("We already have a winner, ignoring human move");
+        let variant = variant(&ui);
+        let side = GameLogic::side_to_move(&sequence_model);
+        if GameLogic::game_outcome(&tiles_model, &sequence_model, variant) != Outcome::InProgress {
+            trace!("The game is over, ignoring human move");
+        } else if !game_mode(&ui).is_person(side) {
+            trace!("{:?} is played by the engine, ignoring click", side);
+        } else if !GameLogic::apply_move(
+            &tiles_model,
+            &sequence_model,
+            variant,
+            clicked_move(&ui, id, side),
+        ) {
+            trace!("Tile {} is not playable, ignoring click", id);
         } else {
-            // Human move
-            for (index, mut tile_data) in tiles_model.iter().enumerate() {
-                if id == tile_data.id {
-                    tile_data.human_clicked = true;
-                    tile_data.empty = false;
-                    tiles_model.set_row_data(index, tile_data);
-
-                    // Add move to sequence
-                    sequence_model.push(Sequence {
-                        id,
-                        player: SharedString::from("H"),
-                    });
-                    break;
-                }
-            }
+            // Move of the person whose turn it is; a new move invalidates the redo history
+            GameLogic::clear_hint(&tiles_model);
+            ui.set_redo_sequence(Rc::new(VecModel::<Sequence>::default()).into());
             ui.set_ttt_tiles(tiles_model.clone().into());
 
-            // Check if Human just won
-            if GameLogic::has_winner(&tiles_model) {
-                trace!("Human wins!");
-            } else {
+            // Check if that move won or filled the board
+            let outcome = GameLogic::game_outcome(&tiles_model, &sequence_model, variant);
+            if outcome != Outcome::InProgress {
+                trace!("Game over after the {:?} move: {:?}", side, outcome);
+            } else if !game_mode(&ui).is_person(side.opponent()) {
                 // Machine's turn
-                let mut founded_state_vec =
-                    GameLogic::search_next_step(&tiles_model, &sequence_model);
-                info!("Machine next-step path: {:?}", founded_state_vec);
+                let mut strategy = strategy_by_index(ui.get_difficulty());
+                let machine_move =
+                    GameLogic::search_next_step(&sequence_model, variant, strategy.as_mut());
+                info!("Machine next move: {:?}", machine_move);
 
                 // Sync the sequence model to UI before we do the actual machine move
                 ui.set_sequence(sequence_model.clone().into());
 
-                if !founded_state_vec.is_empty() {
-                    // The machine's next move is the last tile in the path
-                    let machine_next_tile = founded_state_vec.pop();
-                    if let Some(mn_tile) = machine_next_tile {
-                        // Apply the machine move to the board
-                        for (index, mut tile_data) in tiles_model.iter().enumerate() {
-                            if tile_data.id == mn_tile.field_id && tile_data.empty {
-                                tile_data.machine_clicked = true;
-                                tile_data.empty = false;
-                                tiles_model.set_row_data(index, tile_data);
-                                info!("Machine moves on tile id: {:?}", mn_tile.field_id);
-
-                                // Add move to sequence model
-                                sequence_model.push(Sequence {
-                                    id: mn_tile.field_id,
-                                    player: SharedString::from("M"),
-                                });
-                                break;
-                            }
-                        }
-                        ui.set_ttt_tiles(tiles_model.clone().into());
-                    } else {
-                        info!("Machine's next move not found!");
+                if let Some(mn_move) = machine_move {
+                    // Apply the machine move to the board
+                    if GameLogic::apply_move(&tiles_model, &sequence_model, variant, mn_move) {
+                        info!("Machine moves on tile id: {:?}", mn_move.idx);
                     }
+                    ui.set_ttt_tiles(tiles_model.clone().into());
+                } else {
+                    info!("Machine's next move not found!");
                 }
 
-                // Check if Machine just won
-                if GameLogic::has_winner(&tiles_model) {
-                    trace!("Machine wins!");
+                // Check if Machine just won or filled the board
+                let outcome = GameLogic::game_outcome(&tiles_model, &sequence_model, variant);
+                if outcome != Outcome::InProgress {
+                    trace!("Game over after the machine move: {:?}", outcome);
                 }
             }
         }
 
         // Update UI with final models
+        show_outcome(
+            &ui,
+            &GameLogic::game_outcome(&tiles_model, &sequence_model, variant),
+            &sequence_model,
+        );
         ui.set_sequence(sequence_model.clone().into());
         ui.set_ttt_tiles(tiles_model.clone().into());
     });
 
-    // Handle exit
+    // Handle exit, and closing the window: both keep the session for the next start
+    let ui_weak = ui.as_weak();
     ui.on_exit(move || {
+        let ui = ui_weak.unwrap();
+        let sequence: Vec<Sequence> = ui.get_sequence().iter().collect();
+        save_session(&ui, &Rc::new(VecModel::from(sequence)));
         exit(0);
     });
+    let ui_weak = ui.as_weak();
+    ui.window().on_close_requested(move || {
+        let ui = ui_weak.unwrap();
+        let sequence: Vec<Sequence> = ui.get_sequence().iter().collect();
+        save_session(&ui, &Rc::new(VecModel::from(sequence)));
+        CloseRequestResponse::HideWindow
+    });
+
+    // Self-play games are animated by a timer; changing the speed applies right away
+    let self_play_timer = Rc::new(Timer::default());
+    let ui_weak = ui.as_weak();
+    let timer = self_play_timer.clone();
+    ui.on_self_play_speed_changed(move || {
+        timer.set_interval(self_play_interval(&ui_weak.unwrap()));
+    });
 
     // Handle "restart game" button
     let ui_weak = ui.as_weak();
+    let timer = self_play_timer.clone();
     ui.on_restart_game(move || {
         trace!("Restarting the game");
         let ui = ui_weak.unwrap();
@@ -132,38 +645,96 @@ fn main() {
         // Re-fetch the tile data from UI
         let ttt_tiles: Vec<TileData> = ui.get_ttt_tiles().iter().collect();
         let tiles_model: Rc<VecModel<TileData>> = Rc::new(VecModel::from(ttt_tiles));
+        let sequence_model = Rc::new(VecModel::<Sequence>::default());
 
-        // Reset each tile
-        for (index, mut tile_data) in tiles_model.iter().enumerate() {
-            tile_data.machine_clicked = false;
-            tile_data.human_clicked = false;
-            tile_data.empty = true;
-            tile_data.win_color = DEFAULT_COLOR;
-            tiles_model.set_row_data(index, tile_data);
-        }
-
-        // Clear the sequence
-        let mut sequence: Vec<_> = ui.get_sequence().iter().collect();
-        sequence.clear();
-        let sequence_model = Rc::new(VecModel::from(sequence));
-
-        // Have the Machine make its first move again
-        GameLogic::random_machine_start(&tiles_model, &sequence_model);
+        // Reset the board (laid out again if the variant changed)
+        // and let the Machine open again if it moves first
+        show_variant(&ui);
+        GameLogic::new_game(
+            &tiles_model,
+            &sequence_model,
+            first_player(&ui),
+            variant(&ui),
+        );
 
         info!(
-            "Sequence model size after restart: {}",
+            "Sequence model size after restart of a {} game: {}",
+            variant(&ui).name(),
             sequence_model.row_count()
         );
 
-        // Write back to UI
+        // Write back to UI; the new game is counted in the statistics once it is over
+        ui.set_result_recorded(false);
+        let outcome = GameLogic::game_outcome(&tiles_model, &sequence_model, variant(&ui));
+        show_outcome(&ui, &outcome, &sequence_model);
+        ui.set_redo_sequence(Rc::new(VecModel::<Sequence>::default()).into());
         ui.set_sequence(sequence_model.into());
         ui.set_ttt_tiles(tiles_model.clone().into());
 
         // Force UI to refresh
         let model_notify = ModelNotify::default();
         model_notify.reset();
+
+        if game_mode(&ui) == GameMode::SelfPlay {
+            start_self_play(&ui, &timer);
+        } else {
+            timer.stop();
+        }
+    });
+
+    // Handle "resume": continue the game stored when the app was last closed
+    let ui_weak = ui.as_weak();
+    let timer = self_play_timer.clone();
+    ui.on_resume(move || {
+        let ui = ui_weak.unwrap();
+        ui.set_resume_offered(false);
+        match storage::load_data(SESSION_NAME)
+            .and_then(|text| GameRecord::parse(&text).map_err(|error| error.to_string()))
+        {
+            Ok(record) => continue_game(&ui, &timer, &record),
+            Err(error) => ui.set_status_text(format!("Can't resume: {}", error).into()),
+        }
+    });
+
+    // Handle "save" and "load" buttons
+    let ui_weak = ui.as_weak();
+    ui.on_save_game(move || save_game(&ui_weak.unwrap()));
+    let ui_weak = ui.as_weak();
+    let timer = self_play_timer.clone();
+    ui.on_load_game(move || load_game(&ui_weak.unwrap(), &timer));
+
+    // Handle the replay viewer: "Review" and "Back" toggle it, the rest step through the game
+    let ui_weak = ui.as_weak();
+    ui.on_replay(move || toggle_replay(&ui_weak.unwrap()));
+    let ui_weak = ui.as_weak();
+    ui.on_replay_to(move |ply| replay_to(&ui_weak.unwrap(), ply));
+
+    // Handle "undo" and "redo" buttons
+    let ui_weak = ui.as_weak();
+    ui.on_undo(move || step_history(&ui_weak.unwrap(), false));
+    let ui_weak = ui.as_weak();
+    ui.on_redo(move || step_history(&ui_weak.unwrap(), true));
+
+    // Handle "hint" button: highlight the best Human move and tell how the game goes
+    let ui_weak = ui.as_weak();
+    ui.on_hint(move || {
+        let ui = ui_weak.unwrap();
+        let ttt_tiles: Vec<TileData> = ui.get_ttt_tiles().iter().collect();
+        let tiles_model = Rc::new(VecModel::from(ttt_tiles));
+        let sequence: Vec<Sequence> = ui.get_sequence().iter().collect();
+        let sequence_model = Rc::new(VecModel::from(sequence));
+
+        if let Some(text) = GameLogic::show_hint(&tiles_model, &sequence_model, variant(&ui)) {
+            ui.set_status_text(text.into());
+            ui.set_ttt_tiles(tiles_model.into());
+        }
     });
 
+    // A self-play match restored with the settings starts right away
+    if game_mode(&ui) == GameMode::SelfPlay && !ui.get_resume_offered() {
+        start_self_play(&ui, &self_play_timer);
+    }
+
     // Show the UI
     ui.run().unwrap();
 }
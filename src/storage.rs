@@ -0,0 +1,108 @@
+//! Where the app keeps game records: files in the working directory on the desktop,
+//! and the browser's local storage on the web. Its own data, the statistics and the
+//! session, goes to the user's data directory on the desktop.
+
+/// Stores `text` under `name`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(name: &str, text: &str) -> Result<(), String> {
+    std::fs::write(name, text).map_err(|error| error.to_string())
+}
+
+/// The text stored under `name`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(name: &str) -> Result<String, String> {
+    std::fs::read_to_string(name).map_err(|error| error.to_string())
+}
+
+/// The app's directory for its own data: under `%APPDATA%` on Windows,
+/// `~/Library/Application Support` on macOS and `$XDG_DATA_HOME` (`~/.local/share`)
+/// elsewhere. `None` if the environment names no home.
+#[cfg(not(target_arch = "wasm32"))]
+fn data_dir() -> Option<std::path::PathBuf> {
+    use std::env::var_os;
+    use std::path::PathBuf;
+    let base = if cfg!(windows) {
+        var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+    base.map(|base| base.join("ttt-rs"))
+}
+
+/// Where the app's own data stored under `name` lives; the working directory if
+/// there is no data directory.
+#[cfg(not(target_arch = "wasm32"))]
+fn data_path(name: &str) -> std::path::PathBuf {
+    data_dir().map_or_else(|| name.into(), |dir| dir.join(name))
+}
+
+/// Stores the app's own `text` under `name`, in the user's data directory.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save_data(name: &str, text: &str) -> Result<(), String> {
+    let path = data_path(name);
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(|error| error.to_string())?;
+    }
+    std::fs::write(path, text).map_err(|error| error.to_string())
+}
+
+/// The app's own text stored under `name` by `save_data`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_data(name: &str) -> Result<String, String> {
+    std::fs::read_to_string(data_path(name)).map_err(|error| error.to_string())
+}
+
+/// Today's date (UTC), like `2026-10-18`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn today() -> String {
+    ttt_engine::record::today()
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, String> {
+    web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .ok_or_else(|| "local storage is not available".to_string())
+}
+
+/// Stores `text` under `name`.
+#[cfg(target_arch = "wasm32")]
+pub fn save(name: &str, text: &str) -> Result<(), String> {
+    local_storage()?
+        .set_item(name, text)
+        .map_err(|_| "local storage is full or disabled".to_string())
+}
+
+/// The text stored under `name`.
+#[cfg(target_arch = "wasm32")]
+pub fn load(name: &str) -> Result<String, String> {
+    local_storage()?
+        .get_item(name)
+        .ok()
+        .flatten()
+        .ok_or_else(|| "nothing saved under this name".to_string())
+}
+
+/// Stores the app's own `text` under `name`; the browser keeps it with the records.
+#[cfg(target_arch = "wasm32")]
+pub fn save_data(name: &str, text: &str) -> Result<(), String> {
+    save(name, text)
+}
+
+/// The app's own text stored under `name` by `save_data`.
+#[cfg(target_arch = "wasm32")]
+pub fn load_data(name: &str) -> Result<String, String> {
+    load(name)
+}
+
+/// Today's date (UTC), like `2026-10-18`.
+#[cfg(target_arch = "wasm32")]
+pub fn today() -> String {
+    let seconds = web_sys::js_sys::Date::now() / 1000.0;
+    ttt_engine::record::date_from_unix(seconds as u64)
+}